@@ -1,11 +1,14 @@
-async function __wbg_init() {
+async function __wbg_init(wasm_b64) {
     if (wasm !== undefined) return wasm;
 
     const imports = __wbg_get_imports();
 
     let wasm_binary = Uint8Array.from(atob(wasm_b64), c => c.charCodeAt(0));
 
-    __wbg_init_memory(imports);
+    // the glue of older versions of wasm-bindgen sets the memory up apart
+    if (typeof __wbg_init_memory === "function") {
+        __wbg_init_memory(imports);
+    }
 
     const { instance, module } = await __wbg_load(wasm_binary, imports);
 
@@ -13,6 +16,9 @@ async function __wbg_init() {
 }
 
 export async function main(ns) {
-    await __wbg_init();
-    await main_rs(ns);
+    // the base64 of the wasm is in the script, or in a file next to it
+    await __wbg_init(read_wasm_b64(ns));
+
+    // the jukebox and the rest ship in the same script as the player
+    await dispatch_rs(ns);
 }
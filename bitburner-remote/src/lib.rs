@@ -0,0 +1,78 @@
+//! Pushing files into the game through its Remote API, for the encoder and
+//! the build to put the movies and the player straight onto a server, rather
+//! than having them copied over by hand.
+//!
+//! It's the game that connects, to the port set under Options > Remote API,
+//! so that this end is the server, which takes the first connection to come
+//! in and pushes the files over it as JSON-RPC `pushFile` calls, one after
+//! the other, each answered before the next.
+
+mod websocket;
+
+use std::{
+    io,
+    net::TcpListener,
+};
+
+use serde_json::Value;
+
+use crate::websocket::WebSocket;
+
+/// The port that the game connects to unless it's set otherwise.
+pub const DEFAULT_PORT: u16 = 12525;
+
+/// A file to push, by its name in the game.
+pub struct File {
+    pub filename: String,
+    pub content: String,
+}
+
+/// Waits for the game to connect on `port`, and pushes the files onto
+/// `server` of it, overwriting any of the same name.
+pub fn push_files(
+    port: u16,
+    server: &str,
+    files: &[File],
+) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    eprintln!("waiting for the game to connect on port {}...", port);
+
+    let (stream, _) = listener.accept()?;
+    let mut websocket = WebSocket::accept(stream)?;
+
+    for (id, file) in files.iter().enumerate() {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id + 1,
+            "method": "pushFile",
+            "params": {
+                "filename": file.filename,
+                "content": file.content,
+                "server": server,
+            },
+        });
+        websocket.send(&request.to_string())?;
+
+        let Some(response) = websocket.receive()?
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the game hung up",
+            ));
+        };
+
+        let response = serde_json::from_str::<Value>(&response)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(error) = response.get("error") {
+            let error = error.as_str().map_or(error.to_string(), str::to_owned);
+            return Err(io::Error::other(format!(
+                "the game wouldn't take {}: {}",
+                file.filename, error,
+            )));
+        }
+
+        eprintln!("pushed {} to {}", file.filename, server);
+    }
+
+    websocket.close()
+}
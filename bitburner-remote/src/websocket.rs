@@ -0,0 +1,187 @@
+//! As much of the WebSocket protocol as the Remote API takes, from the end of
+//! the server: the handshake, and messages of text either way, unfragmented
+//! going out, as the game sends nothing larger than a line of JSON back.
+
+use std::{
+    io::{
+        self,
+        BufRead,
+        BufReader,
+        Read,
+        Write,
+    },
+    net::TcpStream,
+};
+
+use base64::engine::Engine as _;
+
+/// What the key of the handshake gets hashed together with.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xa;
+
+/// The most that a message coming back gets to be, in bytes, which is more
+/// than the game ever sends.
+const MAX_MESSAGE_LEN: u64 = 16 * 1024 * 1024;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+pub struct WebSocket {
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+}
+
+impl WebSocket {
+    /// Answers the handshake of whatever connected over `stream`.
+    pub fn accept(mut stream: TcpStream) -> io::Result<WebSocket> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut key = None;
+
+        // the request line, and then the headers up to an empty line
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(invalid("the handshake got cut short"));
+            }
+
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                    key = Some(value.trim().to_owned());
+                }
+            }
+        }
+
+        let key = key.ok_or_else(|| invalid("it isn't a WebSocket"))?;
+        let hash = sha1_smol::Sha1::from(key + ACCEPT_GUID).digest().bytes();
+        let accept = base64::prelude::BASE64_STANDARD.encode(hash);
+
+        write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Accept: {}\r\n\r\n",
+            accept,
+        )?;
+
+        Ok(WebSocket { reader, stream })
+    }
+
+    fn write_frame(
+        &mut self,
+        opcode: u8,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        // what the server sends doesn't get masked
+        let mut header = vec![0x80 | opcode];
+        match payload.len() {
+            len @ 0 ..= 125 => header.push(len as u8),
+            len @ 126 ..= 0xffff => {
+                header.push(126);
+                header.extend((len as u16).to_be_bytes());
+            },
+            len => {
+                header.push(127);
+                header.extend((len as u64).to_be_bytes());
+            },
+        }
+
+        self.stream.write_all(&header)?;
+        self.stream.write_all(payload)?;
+        self.stream.flush()
+    }
+
+    pub fn send(
+        &mut self,
+        text: &str,
+    ) -> io::Result<()> {
+        self.write_frame(OPCODE_TEXT, text.as_bytes())
+    }
+
+    /// Reads a frame, as its opcode, whether it's the last of its message,
+    /// and its payload unmasked.
+    fn read_frame(&mut self) -> io::Result<(u8, bool, Vec<u8>)> {
+        let mut head = [0; 2];
+        self.reader.read_exact(&mut head)?;
+
+        let fin = head[0] & 0x80 != 0;
+        let opcode = head[0] & 0x0f;
+        let masked = head[1] & 0x80 != 0;
+        let len = match head[1] & 0x7f {
+            126 => {
+                let mut len = [0; 2];
+                self.reader.read_exact(&mut len)?;
+                u16::from_be_bytes(len) as u64
+            },
+            127 => {
+                let mut len = [0; 8];
+                self.reader.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            },
+            len => len as u64,
+        };
+
+        if MAX_MESSAGE_LEN < len {
+            return Err(invalid("the game sent back too much"));
+        }
+
+        let mut mask = [0; 4];
+        if masked {
+            self.reader.read_exact(&mut mask)?;
+        }
+
+        let mut payload = vec![0; len as usize];
+        self.reader.read_exact(&mut payload)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        Ok((opcode, fin, payload))
+    }
+
+    /// The next message of text, answering pings along the way, unless the
+    /// other end closes the connection first.
+    pub fn receive(&mut self) -> io::Result<Option<String>> {
+        let mut message = vec![];
+
+        loop {
+            let (opcode, fin, payload) = self.read_frame()?;
+
+            match opcode {
+                OPCODE_TEXT | OPCODE_CONTINUATION => {
+                    message.extend(payload);
+
+                    if MAX_MESSAGE_LEN < message.len() as u64 {
+                        return Err(invalid("the game sent back too much"));
+                    }
+
+                    if fin {
+                        return String::from_utf8(message)
+                            .map(Some)
+                            .map_err(|_| invalid("the game sent back bytes"));
+                    }
+                },
+                OPCODE_PING => self.write_frame(OPCODE_PONG, &payload)?,
+                OPCODE_CLOSE => return Ok(None),
+                _ => {},
+            }
+        }
+    }
+
+    /// Closes the connection, with the code for having gone as it should.
+    pub fn close(mut self) -> io::Result<()> {
+        self.write_frame(OPCODE_CLOSE, &1000u16.to_be_bytes())
+    }
+}
@@ -0,0 +1,135 @@
+//! Decompressing the movie, which is lz4 as `ffmpeg_to_ascii` writes it, but
+//! can just as well be gzip or zlib, as other tools and older pipelines write
+//! it. Which one it is gets told by the magic number that it starts with.
+//!
+//! Movies in plain text don't get decompressed at all.
+//!
+//! Each of lz4 and deflate, the latter being gzip and zlib, is a feature of
+//! its own, and a movie compressed with one that's left out doesn't get read.
+//!
+//! What the player keeps for itself, rather than what the encoder writes,
+//! gets compressed with [`compress`], for [`Decompressor`] to read back.
+
+#[cfg(any(feature = "deflate", feature = "lz4"))]
+use std::io::Write;
+use std::io::{
+    self,
+    BufRead,
+    Read,
+};
+
+#[cfg(feature = "deflate")]
+use flate2::bufread::{
+    GzDecoder,
+    ZlibDecoder,
+};
+#[cfg(all(feature = "deflate", not(feature = "lz4")))]
+use flate2::{
+    write::ZlibEncoder,
+    Compression,
+};
+#[cfg(feature = "lz4")]
+use lz4_flex::frame::{
+    FrameDecoder,
+    FrameEncoder,
+};
+
+const LZ4_MAGIC: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+pub enum Decompressor<R: BufRead> {
+    Plain(R),
+    #[cfg(feature = "lz4")]
+    Lz4(FrameDecoder<R>),
+    #[cfg(feature = "deflate")]
+    Gzip(GzDecoder<R>),
+    #[cfg(feature = "deflate")]
+    Zlib(ZlibDecoder<R>),
+}
+
+/// The error for a movie compressed with what this build leaves out.
+#[cfg(not(all(feature = "deflate", feature = "lz4")))]
+fn left_out(compression: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("it's compressed with {}, which is left out", compression),
+    )
+}
+
+/// Whether the two bytes make for a zlib header, which has the deflate method
+/// in the low bits of the first byte, and the two of them as a multiple of 31.
+fn is_zlib(header: &[u8]) -> bool {
+    match header {
+        [cmf, flg, ..] => {
+            let check = u16::from(*cmf) << 8 | u16::from(*flg);
+            cmf & 0x0f == 8 && check % 31 == 0
+        },
+        _ => false,
+    }
+}
+
+impl<R: BufRead> Decompressor<R> {
+    /// Starts decompressing `reader`, by whichever compression it starts
+    /// with.
+    pub fn new(mut reader: R) -> io::Result<Decompressor<R>> {
+        let start = reader.fill_buf()?;
+
+        if start.starts_with(LZ4_MAGIC) {
+            #[cfg(feature = "lz4")]
+            return Ok(Decompressor::Lz4(FrameDecoder::new(reader)));
+            #[cfg(not(feature = "lz4"))]
+            return Err(left_out("lz4"));
+        }
+        else if start.starts_with(GZIP_MAGIC) {
+            #[cfg(feature = "deflate")]
+            return Ok(Decompressor::Gzip(GzDecoder::new(reader)));
+            #[cfg(not(feature = "deflate"))]
+            return Err(left_out("gzip"));
+        }
+        else if is_zlib(start) {
+            #[cfg(feature = "deflate")]
+            return Ok(Decompressor::Zlib(ZlibDecoder::new(reader)));
+            #[cfg(not(feature = "deflate"))]
+            return Err(left_out("zlib"));
+        }
+        else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "it isn't compressed with lz4, gzip, or zlib",
+            ))
+        }
+    }
+}
+
+impl<R: BufRead> Read for Decompressor<R> {
+    fn read(
+        &mut self,
+        buf: &mut [u8],
+    ) -> io::Result<usize> {
+        match self {
+            Decompressor::Plain(r) => r.read(buf),
+            #[cfg(feature = "lz4")]
+            Decompressor::Lz4(d) => d.read(buf),
+            #[cfg(feature = "deflate")]
+            Decompressor::Gzip(d) => d.read(buf),
+            #[cfg(feature = "deflate")]
+            Decompressor::Zlib(d) => d.read(buf),
+        }
+    }
+}
+
+/// Compresses `bytes` with lz4, or with zlib if lz4 is left out.
+#[cfg(feature = "lz4")]
+pub fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = FrameEncoder::new(vec![]);
+    encoder.write_all(bytes)?;
+    encoder.finish().map_err(io::Error::other)
+}
+
+/// Compresses `bytes` with lz4, or with zlib if lz4 is left out.
+#[cfg(all(feature = "deflate", not(feature = "lz4")))]
+pub fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
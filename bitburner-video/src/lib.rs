@@ -0,0 +1,467 @@
+//! Reading of the movie container written by `ffmpeg_to_ascii`, shared by
+//! the player and the tools of the encoder, so that what gets written and
+//! what gets read can't drift apart.
+//!
+//! After the framerate and dimension lines, the container holds zero or more
+//! chunks, each a `#<TAG> <length>` line followed by exactly `length` bytes of
+//! payload and a newline. The frames follow right after the last chunk.
+//!
+//! The dimensions can be followed by an estimate of the number of frames,
+//! which older files don't have.
+//!
+//! Radio files have a framerate of `0`, dimensions of `0 0`, and no frames.
+//!
+//! What the container gets stored as, be it base64 or split over several
+//! files, is up to whatever reads it to undo, before `Decompressor`.
+//!
+//! The parsing itself only needs `alloc`, with [`split`] for a movie that's
+//! all in memory. Reading it off of a `BufRead` as it's decompressed, which
+//! is what the player does, takes the `std` feature, as do the compressions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod compression;
+#[cfg(feature = "std")]
+mod reader;
+
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::String,
+    vec::Vec,
+};
+use core::fmt;
+
+#[cfg(any(feature = "deflate", feature = "lz4"))]
+pub use compression::compress;
+#[cfg(feature = "std")]
+pub use compression::Decompressor;
+#[cfg(feature = "std")]
+pub use reader::{
+    read_chunks,
+    read_header,
+    Decoded,
+    Frames,
+};
+
+/// The soundtrack: a MIME type line followed by the encoded audio file.
+pub const AUDIO_TAG: &str = "AUDI";
+
+/// The soundtrack reduced to a tune: the step length in milliseconds on its
+/// own line, then a `(MIDI note, steps)` byte pair per note, `0` being a rest.
+pub const TUNE_TAG: &str = "TUNE";
+
+/// A subtitle track: the language on its own line, then cues separated by
+/// blank lines, each a `<start> <end>` line in milliseconds from the first
+/// frame followed by the lines of text. There's a chunk per language.
+pub const SUBTITLES_TAG: &str = "SUBS";
+
+/// The chapters: the dimensions of the thumbnails as `<width> <height>` on
+/// their own line, then for every chapter a `<start> <title>` line, the start
+/// in milliseconds from the first frame, followed by the lines of its
+/// thumbnail.
+pub const CHAPTERS_TAG: &str = "CHAP";
+
+/// The credits of the movie: a `<key>=<value>` line per field, the keys being
+/// `title`, `author`, `source` and `attribution`.
+pub const METADATA_TAG: &str = "META";
+
+/// Word-timed lyrics: cues laid out like those of a subtitle chunk, without
+/// the language line, and with a `<start> <word>` line per word.
+pub const LYRICS_TAG: &str = "LYRC";
+
+/// The largest framerate and dimensions that a movie can have, past which the
+/// header is far likelier to be garbage than a movie that could be played.
+const MAX_FRAMERATE: f64 = 240.;
+const MAX_WIDTH: u32 = 1000;
+const MAX_HEIGHT: u32 = 1000;
+
+
+/// What's wrong with a movie that can't be read.
+#[derive(Debug)]
+pub enum Error {
+    /// It isn't laid out like a movie.
+    Malformed(String),
+    /// It ends short of where it should, as when it got cut short.
+    CutShort(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Error::Malformed(message) | Error::CutShort(message) => {
+                f.write_str(message)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> std::io::Error {
+        let kind = match error {
+            Error::Malformed(_) => std::io::ErrorKind::InvalidData,
+            Error::CutShort(_) => std::io::ErrorKind::UnexpectedEof,
+        };
+
+        std::io::Error::new(kind, error.to_string())
+    }
+}
+
+fn malformed(message: &str) -> Error {
+    Error::Malformed(message.to_owned())
+}
+
+#[derive(Clone, Copy)]
+pub struct Header {
+    pub framerate: f64,
+    pub width: u32,
+    pub height: u32,
+    /// About how many frames there are, if the file says.
+    pub frames: Option<u32>,
+}
+
+impl Header {
+    /// Whether this is a radio file, with nothing but the soundtrack.
+    pub fn is_radio(&self) -> bool {
+        self.height == 0
+    }
+
+    /// Makes sure that `count` frames, as the stream turned out to have, are
+    /// about as many as the file says, which they fall short of if it got
+    /// cut short. The number that it says is only an estimate, which can be
+    /// off by up to a second of frames.
+    pub fn check_count(
+        &self,
+        count: usize,
+    ) -> Result<(), Error> {
+        match self.frames {
+            Some(frames) if count as f64 + self.framerate < frames as f64 => {
+                Err(Error::CutShort(format!(
+                    "it ended after {} of about {} frames",
+                    count, frames,
+                )))
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parses the framerate and the dimension lines of the header, without
+/// their newlines.
+pub fn parse_header(
+    framerate: &str,
+    dimensions: &str,
+) -> Result<Header, Error> {
+    let framerate = framerate
+        .parse::<f64>()
+        .map_err(|_| malformed("cannot parse the framerate"))?;
+
+    let mut dimensions = dimensions.split(' ');
+    let mut next_dimension = || {
+        dimensions
+            .next()
+            .and_then(|d| d.parse::<u32>().ok())
+            .ok_or_else(|| malformed("cannot parse the dimensions"))
+    };
+    let width = next_dimension()?;
+    let height = next_dimension()?;
+    let frames = next_dimension().ok();
+
+    let header = Header {
+        framerate,
+        width,
+        height,
+        frames,
+    };
+
+    if header.is_radio() {
+        if width != 0 {
+            return Err(malformed("a radio file has to have a width of 0"));
+        }
+    }
+    else if !(0. < framerate && framerate <= MAX_FRAMERATE) {
+        return Err(Error::Malformed(format!(
+            "the framerate of {} isn't above 0 and at most {}",
+            framerate, MAX_FRAMERATE,
+        )));
+    }
+    else if !(0 < width && width <= MAX_WIDTH && height <= MAX_HEIGHT) {
+        return Err(Error::Malformed(format!(
+            "the dimensions of {}x{} aren't within 1x1 and {}x{}",
+            width, height, MAX_WIDTH, MAX_HEIGHT,
+        )));
+    }
+
+    Ok(header)
+}
+
+pub struct Chunk {
+    pub tag: String,
+    pub payload: Vec<u8>,
+}
+
+/// Whether what follows is a chunk, rather than the first frame, which goes
+/// by the frames starting with an escape sequence, as the encoder writes
+/// them. The frames of a movie written by hand can start with a `#` of their
+/// own, so there's no looking for chunks in one, and its frames follow the
+/// header.
+fn starts_chunk(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&b'#')
+}
+
+/// Parses the `#<TAG> <length>` line that a chunk starts with, without its
+/// newline, into the tag and the length of the payload.
+pub fn parse_chunk_header(line: &str) -> Result<(&str, usize), Error> {
+    let (tag, length) = line
+        .strip_prefix('#')
+        .and_then(|l| l.split_once(' '))
+        .ok_or_else(|| malformed("malformed chunk header"))?;
+    let length = length
+        .parse::<usize>()
+        .map_err(|_| malformed("malformed chunk length"))?;
+
+    Ok((tag, length))
+}
+
+/// Splits off the line that `bytes` start with, without its newline.
+fn split_line(bytes: &[u8]) -> (&[u8], &[u8]) {
+    match bytes.iter().position(|b| *b == b'\n') {
+        Some(end) => (&bytes[.. end], &bytes[end + 1 ..]),
+        None => (bytes, &[]),
+    }
+}
+
+fn text(line: &[u8]) -> Result<&str, Error> {
+    core::str::from_utf8(line).map_err(|_| malformed("the header isn't text"))
+}
+
+/// Splits the movie, decompressed and all in memory, into its header, its
+/// chunks, and the frames that follow them.
+pub fn split(movie: &[u8]) -> Result<(Header, Vec<Chunk>, &[u8]), Error> {
+    let (framerate, rest) = split_line(movie);
+    let (dimensions, mut rest) = split_line(rest);
+    let header = parse_header(text(framerate)?, text(dimensions)?)?;
+
+    let mut chunks = Vec::new();
+    while starts_chunk(rest) {
+        let (line, after) = split_line(rest);
+        let (tag, length) = parse_chunk_header(text(line)?)?;
+
+        // the payload, and the newline that terminates it
+        if after.len() <= length {
+            return Err(Error::CutShort(format!(
+                "the #{} chunk ends early",
+                tag,
+            )));
+        }
+
+        chunks.push(Chunk {
+            tag: tag.to_owned(),
+            payload: after[.. length].to_vec(),
+        });
+        rest = &after[length + 1 ..];
+    }
+
+    Ok((header, chunks, rest))
+}
+
+/// How many characters of `line` show, leaving out its colour codes and the
+/// newline.
+pub fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // an escape sequence ends with its first letter, like the `m`
+            '\u{1b}' => {
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            },
+            '\n' => {},
+            _ => width += 1,
+        }
+    }
+
+    width
+}
+
+pub fn find_chunks<'a>(
+    chunks: &'a [Chunk],
+    tag: &'a str,
+) -> impl Iterator<Item = &'a Chunk> {
+    chunks.iter().filter(move |c| c.tag == tag)
+}
+
+pub fn find_chunk<'a>(
+    chunks: &'a [Chunk],
+    tag: &str,
+) -> Option<&'a Chunk> {
+    chunks.iter().find(|c| c.tag == tag)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::{
+        self,
+        BufRead,
+        Write,
+    };
+
+    use super::*;
+
+    /// A movie of two frames laid out the way that `ffmpeg_to_ascii` writes
+    /// it, with a chunk whose payload has a newline of its own.
+    fn movie() -> Vec<u8> {
+        let mut movie = vec![];
+        writeln!(movie, "{}", 24).unwrap();
+        writeln!(movie, "{} {} {}", 3, 2, 2).unwrap();
+
+        let payload = b"audio/ogg\n\x00\xff#AUDI 1";
+        writeln!(movie, "#{} {}", AUDIO_TAG, payload.len()).unwrap();
+        movie.write_all(payload).unwrap();
+        writeln!(movie).unwrap();
+        writeln!(movie, "#{} {}", METADATA_TAG, 7).unwrap();
+        movie.write_all(b"title=a").unwrap();
+        writeln!(movie).unwrap();
+
+        movie.write_all(b"\x1b[38;5;1mabc\x1b[0m\ndef\n").unwrap();
+        movie.write_all(b"ghi\njkl\n").unwrap();
+        movie
+    }
+
+    fn read_back(mut reader: impl BufRead) {
+        let header = read_header(&mut reader).unwrap();
+        assert_eq!(header.framerate, 24.);
+        assert_eq!((header.width, header.height), (3, 2));
+        assert_eq!(header.frames, Some(2));
+        assert!(!header.is_radio());
+
+        let chunks = read_chunks(&mut reader).unwrap();
+        assert_eq!(chunks.len(), 2);
+        let audio = find_chunk(&chunks, AUDIO_TAG).unwrap();
+        assert_eq!(audio.payload, b"audio/ogg\n\x00\xff#AUDI 1");
+        let metadata = find_chunk(&chunks, METADATA_TAG).unwrap();
+        assert_eq!(metadata.payload, b"title=a");
+
+        let mut frames = Frames::new(reader, header.width, header.height);
+        frames.check_first().unwrap();
+
+        let mut frame = String::new();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Whole);
+        assert_eq!(frame, "\x1b[38;5;1mabc\x1b[0m\ndef\n");
+
+        frame.clear();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Whole);
+        assert_eq!(frame, "ghi\njkl\n");
+
+        frame.clear();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::End);
+    }
+
+    #[test]
+    fn reads_back_what_the_encoder_writes() {
+        read_back(&movie()[..]);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn reads_back_what_the_encoder_compresses() {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(vec![]);
+        encoder.write_all(&movie()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let decompressor = Decompressor::new(&compressed[..]).unwrap();
+        read_back(io::BufReader::new(decompressor));
+    }
+
+    #[test]
+    fn splits_what_the_encoder_writes() {
+        let movie = movie();
+        let (header, chunks, frames) = split(&movie).unwrap();
+
+        assert_eq!((header.width, header.height), (3, 2));
+        assert_eq!(header.frames, Some(2));
+        assert_eq!(chunks.len(), 2);
+        let audio = find_chunk(&chunks, AUDIO_TAG).unwrap();
+        assert_eq!(audio.payload, b"audio/ogg\n\x00\xff#AUDI 1");
+        assert_eq!(frames, b"\x1b[38;5;1mabc\x1b[0m\ndef\nghi\njkl\n");
+
+        // a chunk cut short, partway through its payload
+        let cut_short = split(&movie[.. 30]);
+        assert!(matches!(cut_short, Err(Error::CutShort(_))));
+    }
+
+    #[test]
+    fn frames_of_the_wrong_width_are_corrupt() {
+        let mut reader = &b"abc\ndef\nab\ndef\nghi\njkl\n"[..];
+        let mut frames = Frames::new(&mut reader, 3, 2);
+
+        let mut frame = String::new();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Whole);
+        frame.clear();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Corrupt);
+        assert!(frame.is_empty());
+        assert_eq!(frames.skip(2).unwrap(), 1);
+    }
+
+    #[test]
+    fn a_frame_cut_short_leaves_nothing_behind() {
+        let mut reader = &b"abc\ndef\nghi\n"[..];
+        let mut frames = Frames::new(&mut reader, 3, 2);
+
+        let mut frame = String::new();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Whole);
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::End);
+        assert_eq!(frame, "abc\ndef\n");
+    }
+
+    #[test]
+    fn frames_written_by_hand_can_start_with_a_hash() {
+        let mut reader = &b"4\n5 2 2\n#x 33\n#####\n## ##\n#   #\n"[..];
+        let header = read_header(&mut reader).unwrap();
+
+        let mut frames = Frames::new(reader, header.width, header.height);
+        frames.check_first().unwrap();
+
+        let mut frame = String::new();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Whole);
+        assert_eq!(frame, "#x 33\n#####\n");
+        frame.clear();
+        assert!(frames.read_frame(&mut frame).unwrap() == Decoded::Whole);
+        assert_eq!(frame, "## ##\n#   #\n");
+    }
+
+    #[test]
+    fn movies_cut_short_fall_short_of_the_count() {
+        let header = read_header(&mut &b"24\n3 2 100\n"[..]).unwrap();
+        assert!(header.check_count(100).is_ok());
+        assert!(header.check_count(80).is_ok());
+        assert!(header.check_count(120).is_ok());
+
+        let error = header.check_count(50).unwrap_err();
+        assert!(matches!(error, Error::CutShort(_)));
+        assert_eq!(error.to_string(), "it ended after 50 of about 100 frames");
+        let error = io::Error::from(error);
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        // older files don't say
+        let header = read_header(&mut &b"24\n3 2\n"[..]).unwrap();
+        assert!(header.check_count(0).is_ok());
+    }
+
+    #[test]
+    fn radio_files_have_no_picture() {
+        let header = read_header(&mut &b"0\n0 0\n"[..]).unwrap();
+        assert!(header.is_radio());
+
+        assert!(read_header(&mut &b"0\n3 0\n"[..]).is_err());
+        assert!(read_header(&mut &b"24\n0 2\n"[..]).is_err());
+    }
+}
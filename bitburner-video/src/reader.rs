@@ -0,0 +1,270 @@
+//! Reading the movie off of a [`BufRead`], a bit at a time as it gets
+//! decompressed, rather than all of it at once.
+
+use std::{
+    collections::VecDeque,
+    io::{
+        self,
+        BufRead,
+    },
+};
+
+use crate::{
+    parse_chunk_header,
+    parse_header,
+    starts_chunk,
+    visible_width,
+    Chunk,
+    Header,
+};
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    if line.ends_with('\n') {
+        line.pop();
+    }
+
+    Ok(line)
+}
+
+pub fn read_header(reader: &mut impl BufRead) -> io::Result<Header> {
+    let framerate = read_line(reader)?;
+    let dimensions = read_line(reader)?;
+
+    Ok(parse_header(&framerate, &dimensions)?)
+}
+
+/// Reads every chunk up until the first frame.
+pub fn read_chunks(reader: &mut impl BufRead) -> io::Result<Vec<Chunk>> {
+    let mut chunks = vec![];
+
+    while starts_chunk(reader.fill_buf()?) {
+        let line = read_line(reader)?;
+        let (tag, length) = parse_chunk_header(&line)?;
+
+        let mut payload = vec![0; length];
+        reader.read_exact(&mut payload)?;
+
+        // the newline that terminates the payload
+        reader.read_exact(&mut [0])?;
+
+        chunks.push(Chunk {
+            tag: tag.to_owned(),
+            payload,
+        });
+    }
+
+    Ok(chunks)
+}
+
+/// What reading a frame came to.
+#[derive(PartialEq)]
+pub enum Decoded {
+    Whole,
+    /// The frame was there, but a line of it wasn't text, or wasn't as wide
+    /// as the others, so there's nothing of it to show.
+    Corrupt,
+    /// There wasn't a whole frame left.
+    End,
+}
+
+/// Reads the frames that follow the chunks, one at a time.
+pub struct Frames<R> {
+    reader: R,
+    /// The characters per line.
+    width: u32,
+    /// The lines per frame.
+    height: u32,
+    /// The frames that got decoded ahead of time, to be read before any
+    /// others, the corrupt ones being left empty.
+    ahead: VecDeque<String>,
+    /// The buffers of frames that were read, for more to be decoded ahead
+    /// into without allocating.
+    spare: Vec<String>,
+}
+
+impl<R: BufRead> Frames<R> {
+    pub fn new(
+        reader: R,
+        width: u32,
+        height: u32,
+    ) -> Frames<R> {
+        Frames {
+            reader,
+            width,
+            height,
+            ahead: VecDeque::new(),
+            spare: vec![],
+        }
+    }
+
+    /// Appends the lines of the next frame to `frame`, unless the frame is
+    /// corrupt or there wasn't a whole one left.
+    pub fn read_frame(
+        &mut self,
+        frame: &mut String,
+    ) -> io::Result<Decoded> {
+        match self.ahead.pop_front() {
+            Some(ahead) if ahead.is_empty() => {
+                self.recycle(ahead);
+                Ok(Decoded::Corrupt)
+            },
+            Some(mut ahead) => {
+                // an empty buffer can be handed over outright
+                match frame.is_empty() {
+                    true => std::mem::swap(frame, &mut ahead),
+                    false => *frame += &ahead,
+                }
+
+                self.recycle(ahead);
+                Ok(Decoded::Whole)
+            },
+            None => self.decode_frame(frame),
+        }
+    }
+
+    /// Decodes up to `count` frames ahead of time, returning how many there
+    /// were, so that reading them later takes no decompressing.
+    pub fn read_ahead(
+        &mut self,
+        count: usize,
+    ) -> io::Result<usize> {
+        for read in 0 .. count {
+            let mut frame = self.spare.pop().unwrap_or_default();
+            let decoded = self.decode_frame(&mut frame);
+
+            match decoded {
+                Ok(Decoded::Whole | Decoded::Corrupt) => {
+                    self.ahead.push_back(frame)
+                },
+                _ => {
+                    self.recycle(frame);
+                    return decoded.map(|_| read);
+                },
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn recycle(
+        &mut self,
+        mut frame: String,
+    ) {
+        frame.clear();
+        self.spare.push(frame);
+    }
+
+    /// Decodes the first frame ahead of time, making sure that it's there
+    /// and that its lines are as wide as the header says, so that a file
+    /// that's laid out wrong is told apart before anything goes up, rather
+    /// than passed off as having every frame corrupt.
+    pub fn check_first(&mut self) -> io::Result<()> {
+        let mut frame = self.spare.pop().unwrap_or_default();
+
+        for number in 1 ..= self.height {
+            let start = frame.len();
+            if self.reader.read_line(&mut frame)? == 0 {
+                return Err(invalid("there's no whole frame"));
+            }
+
+            let line_width = visible_width(&frame[start ..]);
+            if line_width != self.width as usize {
+                return Err(invalid(&format!(
+                    "line {} of the first frame is {} wide rather than {}",
+                    number, line_width, self.width,
+                )));
+            }
+        }
+
+        self.ahead.push_front(frame);
+        Ok(())
+    }
+
+    /// How many bytes the frames decoded ahead of time, and the buffers kept
+    /// for them, take up.
+    pub fn buffered(&self) -> usize {
+        let ahead = self.ahead.iter().map(|f| f.capacity());
+        let spare = self.spare.iter().map(|f| f.capacity());
+
+        ahead.chain(spare).sum()
+    }
+
+    /// How many frames got decoded ahead of time and are yet to be read.
+    pub fn ahead(&self) -> usize {
+        self.ahead.len()
+    }
+
+    fn decode_frame(
+        &mut self,
+        frame: &mut String,
+    ) -> io::Result<Decoded> {
+        let start = frame.len();
+        let mut corrupt = false;
+
+        for _ in 0 .. self.height {
+            let line_start = frame.len();
+
+            match self.reader.read_line(frame) {
+                // what there was of the last frame doesn't get left behind,
+                // for the buffer to be read into again once it's reopened
+                Ok(0) => {
+                    frame.truncate(start);
+                    return Ok(Decoded::End);
+                },
+                Ok(_) => {
+                    let line_width = visible_width(&frame[line_start ..]);
+                    corrupt |= line_width != self.width as usize;
+                },
+                // a line that isn't text gets thrown away as a whole, which
+                // leaves the rest of the frames be, unlike an error of the
+                // decompression, which carries one of its own
+                Err(e)
+                    if e.kind() == io::ErrorKind::InvalidData
+                        && e.get_ref().is_none() =>
+                {
+                    corrupt = true
+                },
+                Err(e) => return Err(e),
+            }
+        }
+
+        match corrupt {
+            true => {
+                frame.truncate(start);
+                Ok(Decoded::Corrupt)
+            },
+            false => Ok(Decoded::Whole),
+        }
+    }
+
+    /// Skips up to `count` frames, returning how many there were. The lines
+    /// get thrown away as they're decompressed, without being copied out.
+    pub fn skip(
+        &mut self,
+        count: usize,
+    ) -> io::Result<usize> {
+        let ahead = count.min(self.ahead.len());
+        for _ in 0 .. ahead {
+            if let Some(frame) = self.ahead.pop_front() {
+                self.recycle(frame);
+            }
+        }
+
+        for skipped in ahead .. count {
+            for _ in 0 .. self.height {
+                if self.reader.skip_until(b'\n')? == 0 {
+                    return Ok(skipped);
+                }
+            }
+        }
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    io,
+    path::Path,
+    process::Command,
+};
+
+use crate::{
+    probe_streams,
+    trim::Trim,
+};
+
+/// The MIME type of what [`extract_audio`] produces, for the player to hand to
+/// the browser.
+const AUDIO_MIME: &str = "audio/ogg";
+
+/// Where the soundtrack comes from.
+#[derive(Clone, Copy)]
+pub struct AudioInput<'a> {
+    pub file: &'a Path,
+    pub trim: Trim,
+    /// How much later than the video the soundtrack plays, in milliseconds.
+    /// Negative values make it play earlier.
+    pub offset_ms: i64,
+    /// Which of the audio streams of the file to use, counting from `0`.
+    pub track: usize,
+}
+
+/// Creates an ffmpeg invocation that reads the soundtrack of the video and
+/// drops everything else. The caller adds the output options.
+pub fn ffmpeg_audio(input: &AudioInput) -> Command {
+    let mut command = Command::new("ffmpeg");
+    command.arg("-hide_banner").arg("-loglevel").arg("error");
+
+    // playing earlier is the same as skipping the start, while playing later
+    // needs silence in front
+    let mut trim = input.trim;
+    if input.offset_ms < 0 {
+        trim.start += -input.offset_ms as f64 / 1000.;
+    }
+
+    trim.add_input_args(&mut command);
+
+    command
+        .arg("-i")
+        .arg(format!("{}", input.file.display()))
+        .arg("-vn")
+        .arg("-map")
+        .arg(format!("0:a:{}", input.track));
+
+    if 0 < input.offset_ms {
+        command
+            .arg("-af")
+            .arg(format!("adelay={}:all=1", input.offset_ms));
+
+        // the delay pushes the end of the soundtrack past the trim
+        if let Some(duration) = input.trim.duration {
+            command.arg("-t").arg(duration.to_string());
+        }
+    }
+
+    command
+}
+
+/// Extracts the soundtrack of the video as Ogg Opus, returning the payload of
+/// an audio chunk.
+pub fn extract_audio(
+    input: &AudioInput,
+    bitrate: &str,
+) -> io::Result<Vec<u8>> {
+    let output = ffmpeg_audio(input)
+        .arg("-c:a")
+        .arg("libopus")
+        .arg("-b:a")
+        .arg(bitrate)
+        .arg("-f")
+        .arg("ogg")
+        .arg("-")
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other("ffmpeg could not extract the audio"));
+    }
+
+    let mut payload = format!("{}\n", AUDIO_MIME).into_bytes();
+    payload.extend_from_slice(&output.stdout);
+
+    Ok(payload)
+}
+
+/// Decodes the soundtrack into mono samples at the sample rate, for
+/// analysis.
+pub fn read_samples(
+    input: &AudioInput,
+    sample_rate: usize,
+) -> io::Result<Vec<f32>> {
+    let output = ffmpeg_audio(input)
+        .arg("-ac")
+        .arg("1")
+        .arg("-ar")
+        .arg(sample_rate.to_string())
+        .arg("-f")
+        .arg("f32le")
+        .arg("-")
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other("ffmpeg could not extract the audio"));
+    }
+
+    Ok(output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// What ffprobe knows about an audio stream, for picking a track.
+pub struct TrackInfo {
+    pub codec: String,
+    pub channels: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Lists the audio streams of the file, in the order that `--audio-track`
+/// counts them.
+pub fn list_tracks(file: &Path) -> io::Result<Vec<TrackInfo>> {
+    let streams = probe_streams(
+        file,
+        "a",
+        "stream=codec_name,channels:stream_tags=language,title",
+    )?;
+
+    Ok(streams
+        .into_iter()
+        .map(|mut stream| TrackInfo {
+            codec: stream.remove("codec_name").unwrap_or_default(),
+            channels: stream.remove("channels").unwrap_or_default(),
+            language: stream.remove("TAG:language"),
+            title: stream.remove("TAG:title"),
+        })
+        .collect())
+}
@@ -0,0 +1,140 @@
+//! The chapters of the video, each with a tiny thumbnail that the player
+//! shows in its chapter strip.
+
+use std::{
+    io,
+    path::Path,
+    process::Command,
+};
+
+use termcolor::Buffer;
+
+use crate::{
+    probe_sections,
+    render_frame,
+    trim::Trim,
+};
+
+pub struct Chapter {
+    /// When the chapter starts and ends, in milliseconds.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub title: String,
+}
+
+/// Reads the chapters of the video, in order. Chapters without a title get
+/// numbered instead.
+pub fn list_chapters(video_file: &Path) -> io::Result<Vec<Chapter>> {
+    let sections =
+        probe_sections(video_file, &["-show_chapters"], "[CHAPTER]")?;
+
+    let seconds_to_ms = |secs: Option<&String>| {
+        secs.and_then(|s| s.parse::<f64>().ok())
+            .map(|s| (s * 1000.) as u64)
+    };
+
+    let mut chapters = sections
+        .iter()
+        .enumerate()
+        .filter_map(|(number, section)| {
+            let title = section
+                .get("TAG:title")
+                .map(|t| t.replace(['\r', '\n'], " "))
+                .unwrap_or_else(|| format!("Chapter {}", number + 1));
+
+            Some(Chapter {
+                start_ms: seconds_to_ms(section.get("start_time"))?,
+                end_ms: seconds_to_ms(section.get("end_time"))?,
+                title,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    chapters.sort_by_key(|c| c.start_ms);
+    Ok(chapters)
+}
+
+/// Makes the chapters relative to the start of the trim, dropping the ones
+/// that fall outside of it. A chapter that got cut into starts with the trim.
+pub fn apply_trim(
+    chapters: Vec<Chapter>,
+    trim: Trim,
+) -> Vec<Chapter> {
+    let start = (trim.start * 1000.) as u64;
+    let end = trim.duration.map(|d| start + (d * 1000.) as u64);
+
+    chapters
+        .into_iter()
+        .filter(|c| start < c.end_ms && end.is_none_or(|end| c.start_ms < end))
+        .map(|c| Chapter {
+            start_ms: c.start_ms.saturating_sub(start),
+            end_ms: end.map_or(c.end_ms, |end| c.end_ms.min(end)) - start,
+            title: c.title,
+        })
+        .collect()
+}
+
+/// Renders the frame halfway into the chapter at the size of a thumbnail.
+/// The first frame of a chapter tends to be a fade or a title card, which
+/// would make for a strip of black thumbnails.
+fn thumbnail(
+    video_file: &Path,
+    chapter: &Chapter,
+    trim: Trim,
+    (width, height): (u32, u32),
+) -> io::Result<Buffer> {
+    let middle_ms = (chapter.start_ms + chapter.end_ms) / 2;
+    let at = trim.start + middle_ms as f64 / 1000.;
+
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-ss")
+        .arg(at.to_string())
+        .arg("-i")
+        .arg(format!("{}", video_file.display()))
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-vf")
+        .arg(format!("scale={}:{}", width, height))
+        .arg("-f")
+        .arg("rawvideo")
+        .arg("-pix_fmt")
+        .arg("bgra")
+        .arg("-")
+        .output()?;
+
+    if !output.status.success()
+        || output.stdout.len() != (width * height * 4) as usize
+    {
+        return Err(io::Error::other("ffmpeg could not grab the thumbnail"));
+    }
+
+    let mut buffer = Buffer::ansi();
+    render_frame(width, height, output.stdout, &mut buffer);
+    Ok(buffer)
+}
+
+/// Creates the payload of a chapter chunk, rendering a `thumbnail_size`
+/// thumbnail of every chapter. The chapters must be relative to the trim.
+pub fn chunk_payload(
+    video_file: &Path,
+    chapters: &[Chapter],
+    trim: Trim,
+    thumbnail_size: (u32, u32),
+) -> io::Result<Vec<u8>> {
+    let mut payload =
+        format!("{} {}\n", thumbnail_size.0, thumbnail_size.1).into_bytes();
+
+    for chapter in chapters {
+        let thumbnail = thumbnail(video_file, chapter, trim, thumbnail_size)?;
+
+        payload.extend(
+            format!("{} {}\n", chapter.start_ms, chapter.title).into_bytes(),
+        );
+        payload.extend(thumbnail.as_slice());
+    }
+
+    Ok(payload)
+}
@@ -0,0 +1,91 @@
+//! Reduction of a soundtrack into a monophonic tune that the player
+//! synthesizes, for when embedding the real audio would be too large.
+
+use std::io;
+
+use crate::audio::{
+    read_samples,
+    AudioInput,
+};
+
+/// The sample rate that the pitch tracking works at. Melodies rarely go above
+/// 1 kHz, so this is plenty.
+const SAMPLE_RATE: usize = 8000;
+
+/// The length of a single step of the tune in milliseconds.
+const STEP_MS: usize = 50;
+
+/// The number of samples looked at to find the pitch of a step.
+const WINDOW: usize = 512;
+
+/// The range of detected pitches, in Hz.
+const MIN_PITCH: usize = 80;
+const MAX_PITCH: usize = 1000;
+
+/// Steps quieter than this are rests.
+const SILENCE_RMS: f32 = 0.02;
+
+/// Steps whose normalized autocorrelation peak is below this have no clear
+/// pitch, and are rests as well.
+const MIN_CLARITY: f32 = 0.6;
+
+/// Finds the MIDI note of the window through its autocorrelation, with `0`
+/// meaning a rest.
+fn detect_note(window: &[f32]) -> u8 {
+    let energy = window.iter().map(|s| s * s).sum::<f32>();
+    if (energy / window.len() as f32).sqrt() < SILENCE_RMS {
+        return 0;
+    }
+
+    let mut best_lag = 0;
+    let mut best_clarity = 0.;
+
+    for lag in SAMPLE_RATE / MAX_PITCH ..= SAMPLE_RATE / MIN_PITCH {
+        let (head, tail) = (&window[.. window.len() - lag], &window[lag ..]);
+
+        let correlation =
+            head.iter().zip(tail).map(|(a, b)| a * b).sum::<f32>();
+        let head_energy = head.iter().map(|s| s * s).sum::<f32>();
+        let tail_energy = tail.iter().map(|s| s * s).sum::<f32>();
+        let clarity =
+            correlation / (head_energy * tail_energy).sqrt().max(1e-9);
+
+        if best_clarity < clarity {
+            best_clarity = clarity;
+            best_lag = lag;
+        }
+    }
+
+    if best_clarity < MIN_CLARITY {
+        return 0;
+    }
+
+    let pitch = SAMPLE_RATE as f32 / best_lag as f32;
+    (69. + 12. * (pitch / 440.).log2()).round().clamp(1., 127.) as u8
+}
+
+/// Pitch-tracks the soundtrack of the video, returning the payload of a tune
+/// chunk: the step length in milliseconds on its own line, then a
+/// `(note, steps)` byte pair for each run of the same note.
+pub fn extract_chiptune(input: &AudioInput) -> io::Result<Vec<u8>> {
+    let samples = read_samples(input, SAMPLE_RATE)?;
+    let step = SAMPLE_RATE * STEP_MS / 1000;
+
+    let mut runs: Vec<(u8, u8)> = vec![];
+
+    for start in (0 .. samples.len().saturating_sub(WINDOW)).step_by(step) {
+        let note = detect_note(&samples[start .. start + WINDOW]);
+
+        match runs.last_mut() {
+            Some((last, steps)) if *last == note && *steps < u8::MAX => {
+                *steps += 1
+            },
+            _ => runs.push((note, 1)),
+        }
+    }
+
+    let mut payload = format!("{}\n", STEP_MS).into_bytes();
+    payload.extend(runs.into_iter().flat_map(|(note, steps)| [note, steps]));
+
+    Ok(payload)
+}
@@ -0,0 +1,41 @@
+//! Writing of the movie container that the player reads.
+//!
+//! The container is the stream that gets LZ4-compressed. It consists of:
+//!
+//! - the framerate on its own line
+//! - the dimensions of a frame as `<width> <height>`, followed by an estimate
+//!   of the number of frames
+//! - zero or more chunks, each a `#<TAG> <length>` line followed by exactly
+//!   `length` bytes of payload and a newline
+//! - the frames, `height` lines per frame
+//!
+//! Frame lines always start with an escape sequence, so a leading `#` is
+//! enough for the player to tell chunks apart from the first frame.
+//!
+//! Radio files, made out of audio files, have a framerate of `0`, dimensions
+//! of `0 0`, and no frames.
+
+use std::io::{
+    self,
+    Write,
+};
+
+// the tags are the ones that the player reads
+pub use bitburner_video::{
+    AUDIO_TAG,
+    CHAPTERS_TAG,
+    LYRICS_TAG,
+    METADATA_TAG,
+    SUBTITLES_TAG,
+    TUNE_TAG,
+};
+
+pub fn write_chunk(
+    writer: &mut impl Write,
+    tag: &str,
+    payload: &[u8],
+) -> io::Result<()> {
+    writeln!(writer, "#{} {}", tag, payload.len())?;
+    writer.write_all(payload)?;
+    writeln!(writer)
+}
@@ -0,0 +1,196 @@
+//! Word-timed lyrics for karaoke, from enhanced LRC files.
+//!
+//! Every line of an LRC file starts with a `[mm:ss.xx]` timestamp, and the
+//! enhanced flavor adds a `<mm:ss.xx>` timestamp in front of every word:
+//!
+//! ```text
+//! [00:12.00]<00:12.00>Never <00:12.40>gonna <00:12.90>give
+//! ```
+//!
+//! Lines without word timestamps are taken as a single word.
+
+use std::{
+    fs,
+    io,
+    path::Path,
+};
+
+use crate::{
+    subtitles::{
+        self,
+        Cue,
+    },
+    trim::Trim,
+};
+
+/// How long the last line stays up after its last word, in milliseconds.
+const LAST_LINE_MS: u64 = 5000;
+
+pub struct Word {
+    pub start_ms: u64,
+    pub text: String,
+}
+
+pub struct Line {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Every word lasts until the next one, the last until the end of the line.
+    pub words: Vec<Word>,
+}
+
+/// Parses an LRC timestamp, `mm:ss.xx`, into milliseconds.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (minutes, secs) = timestamp.trim().split_once(':')?;
+    let secs = secs.parse::<f64>().ok()?;
+
+    Some(minutes.parse::<u64>().ok()? * 60_000 + (secs * 1000.).round() as u64)
+}
+
+/// Splits the leading `[...]` timestamps off the line. Tags such as `[ar:...]`
+/// aren't timestamps, so these lines have none.
+fn line_timestamps(line: &str) -> (Vec<u64>, &str) {
+    let mut timestamps = vec![];
+    let mut rest = line.trim();
+
+    while let Some((tag, after)) =
+        rest.strip_prefix('[').and_then(|r| r.split_once(']'))
+    {
+        match parse_timestamp(tag) {
+            Some(timestamp) => timestamps.push(timestamp),
+            None => return (vec![], rest),
+        }
+
+        rest = after;
+    }
+
+    (timestamps, rest)
+}
+
+/// Splits the text into words, the ones with a `<...>` timestamp starting at
+/// it and the others along with the word before them, or at `start_ms`.
+fn parse_words(
+    text: &str,
+    start_ms: u64,
+) -> Vec<Word> {
+    let mut words = vec![];
+    let mut at = start_ms;
+
+    for part in text.split('<') {
+        let part = match part.split_once('>') {
+            Some((tag, rest)) if parse_timestamp(tag).is_some() => {
+                at = parse_timestamp(tag).unwrap();
+                rest
+            },
+            _ => part,
+        };
+
+        for text in part.split_whitespace() {
+            words.push(Word {
+                start_ms: at,
+                text: text.to_owned(),
+            });
+        }
+    }
+
+    words
+}
+
+pub fn parse_lrc(lrc: &str) -> Vec<Line> {
+    // lines repeated with several timestamps, the chorus most of all, are
+    // spelled out before sorting
+    let mut timed = vec![];
+    for line in lrc.lines() {
+        let (timestamps, text) = line_timestamps(line);
+
+        for timestamp in timestamps {
+            timed.push((timestamp, text));
+        }
+    }
+
+    timed.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut lines: Vec<Line> = vec![];
+
+    for (index, (start_ms, text)) in timed.iter().enumerate() {
+        let mut words = parse_words(text, *start_ms);
+
+        // empty lines only end the line before them
+        let Some(first) = words.first().map(|w| w.start_ms)
+        else {
+            continue;
+        };
+
+        let end_ms = match timed.get(index + 1) {
+            Some((next, _)) => *next,
+            None => {
+                words.last().unwrap().start_ms.max(*start_ms) + LAST_LINE_MS
+            },
+        };
+
+        // the word timestamps of a repeated line are only right for one of
+        // the repetitions, so the others get theirs moved along
+        if !(*start_ms <= first && first < end_ms) {
+            for word in words.iter_mut() {
+                word.start_ms =
+                    (word.start_ms + start_ms).saturating_sub(first);
+            }
+        }
+
+        lines.push(Line {
+            start_ms: *start_ms,
+            end_ms,
+            words,
+        });
+    }
+
+    lines
+}
+
+pub fn read_lrc(path: &Path) -> io::Result<Vec<Line>> {
+    Ok(parse_lrc(&fs::read_to_string(path)?))
+}
+
+/// Makes the lines relative to the start of the trim, dropping the ones that
+/// fall outside of it.
+pub fn apply_trim(
+    lines: Vec<Line>,
+    trim: Trim,
+) -> Vec<Line> {
+    let start = (trim.start * 1000.) as u64;
+    let end = trim.duration.map(|d| start + (d * 1000.) as u64);
+
+    lines
+        .into_iter()
+        .filter(|l| start < l.end_ms && end.is_none_or(|end| l.start_ms < end))
+        .map(|l| Line {
+            start_ms: l.start_ms.saturating_sub(start),
+            end_ms: l.end_ms - start,
+            words: l
+                .words
+                .into_iter()
+                .map(|w| Word {
+                    start_ms: w.start_ms.saturating_sub(start),
+                    text: w.text,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Creates the payload of a lyrics chunk.
+pub fn chunk_payload(lines: &[Line]) -> Vec<u8> {
+    let cues = lines
+        .iter()
+        .map(|line| Cue {
+            start_ms: line.start_ms,
+            end_ms: line.end_ms,
+            lines: line
+                .words
+                .iter()
+                .map(|w| format!("{} {}", w.start_ms, w.text))
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    subtitles::write_cues(&cues).into_bytes()
+}
@@ -1,8 +1,8 @@
 use std::{
+    collections::HashMap,
     io::{
         self,
         Error,
-        ErrorKind,
         Read,
     },
     path::{
@@ -13,15 +13,31 @@ use std::{
     str,
 };
 
+mod audio;
+mod chapters;
+mod chiptune;
+mod container;
+mod lyrics;
+mod metadata;
+mod probe;
+mod subtitles;
+mod trim;
+mod upload;
+mod visualizer;
+#[cfg(feature = "auto-subs")]
+mod whisper;
+
 use termcolor::Buffer;
 use asciify::AsciiBuilder;
 use clap::Parser;
 use image::ImageBuffer;
+use trim::Trim;
+use visualizer::Visualizer;
 
 fn get_video_dimensions(file_path: &Path) -> io::Result<(u32, u32)> {
     // Use the ffprobe command to get video information
     let output = Command::new("ffprobe")
-        .args(&[
+        .args([
             "-v",
             "error",
             "-select_streams",
@@ -35,13 +51,13 @@ fn get_video_dimensions(file_path: &Path) -> io::Result<(u32, u32)> {
         .output()?;
 
     if !output.status.success() {
-        return Err(Error::new(ErrorKind::Other, "Error executing ffprobe"));
+        return Err(Error::other("Error executing ffprobe"));
     }
 
     // Convert the output to a String
     let output_str = str::from_utf8(&output.stdout)
         .map_err(|_| {
-            Error::new(ErrorKind::Other, "Error converting output to String")
+            Error::other("Error converting output to String")
         })
         .unwrap(); //?;
 
@@ -49,26 +65,98 @@ fn get_video_dimensions(file_path: &Path) -> io::Result<(u32, u32)> {
     let v: Vec<&str> = output_str.trim().split(',').collect();
 
     if v.len() != 2 {
-        return Err(Error::new(
-            ErrorKind::Other,
-            "Invalid file or video channel",
-        ));
+        return Err(Error::other("Invalid file or video channel"));
     }
 
     let width = v[0]
         .parse::<u32>()
-        .map_err(|_| Error::new(ErrorKind::Other, "Error parsing width"))
+        .map_err(|_| Error::other("Error parsing width"))
         .unwrap(); //?;
     let height = v[1]
         .parse::<u32>()
-        .map_err(|_| Error::new(ErrorKind::Other, "Error parsing height"))?;
+        .map_err(|_| Error::other("Error parsing height"))?;
 
     Ok((width, height))
 }
 
+/// Whether the file has a video stream at all, as opposed to being an audio
+/// file.
+fn has_video_stream(file_path: &Path) -> io::Result<bool> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v",
+            "-show_entries",
+            "stream=index",
+            "-of",
+            "csv=p=0",
+            &format!("{}", file_path.display()),
+        ])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::other("Error executing ffprobe"));
+    }
+
+    Ok(!output.stdout.trim_ascii().is_empty())
+}
+
+/// Runs ffprobe with the options, returning every `section` (`[STREAM]`,
+/// `[CHAPTER]`, ...) of its output as its entries by their key. Tags are keyed
+/// as `TAG:<name>`.
+fn probe_sections(
+    file_path: &Path,
+    options: &[&str],
+    section: &str,
+) -> io::Result<Vec<HashMap<String, String>>> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error"])
+        .args(options)
+        .arg(format!("{}", file_path.display()))
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::other("Error executing ffprobe"));
+    }
+
+    // every section is a header line followed by key=value lines
+    let mut sections = vec![];
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line == section {
+            sections.push(HashMap::new());
+            continue;
+        }
+
+        if let (Some(entries), Some((key, value))) =
+            (sections.last_mut(), line.split_once('='))
+        {
+            entries.insert(key.to_owned(), value.to_owned());
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Runs ffprobe over the streams picked by `selector` (`a`, `s`, ...),
+/// returning the requested `entries` of every stream by their key. Tags are
+/// keyed as `TAG:<name>`.
+fn probe_streams(
+    file_path: &Path,
+    selector: &str,
+    entries: &str,
+) -> io::Result<Vec<HashMap<String, String>>> {
+    probe_sections(
+        file_path,
+        &["-select_streams", selector, "-show_entries", entries],
+        "[STREAM]",
+    )
+}
+
 fn get_video_fps(video_file: &Path) -> io::Result<f64> {
     let output = Command::new("ffprobe")
-        .args(&[
+        .args([
             "-v", "error",
             "-select_streams", "v:0",
             "-show_entries", "stream=r_frame_rate",
@@ -78,35 +166,57 @@ fn get_video_fps(video_file: &Path) -> io::Result<f64> {
         .output()?;
 
     if !output.status.success() {
-        return Err(io::Error::new(io::ErrorKind::Other, "ffprobe command failed"));
+        return Err(io::Error::other("ffprobe command failed"));
     }
 
     let frame_rate_str = String::from_utf8_lossy(&output.stdout);
     let mut frame_rate = frame_rate_str.trim().split("/");
-    let numerator = frame_rate.next().ok_or_else(|| io::Error::new(io::ErrorKind::Other, "failed to parse frame rate"))?.parse::<f64>()
-        .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to parse frame rate"))?;
-    let denominator = frame_rate.next().ok_or_else(|| io::Error::new(io::ErrorKind::Other, "failed to parse frame rate"))?.parse::<f64>()
-        .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to parse frame rate"))?;
+    let numerator = frame_rate.next().ok_or_else(|| io::Error::other("failed to parse frame rate"))?.parse::<f64>()
+        .map_err(|_| io::Error::other("failed to parse frame rate"))?;
+    let denominator = frame_rate.next().ok_or_else(|| io::Error::other("failed to parse frame rate"))?.parse::<f64>()
+        .map_err(|_| io::Error::other("failed to parse frame rate"))?;
 
     Ok(numerator / denominator)
 }
 
+/// The length of the file in seconds, as far as its container knows.
+fn get_duration(file_path: &Path) -> io::Result<f64> {
+    let format = probe_sections(
+        file_path,
+        &["-show_entries", "format=duration"],
+        "[FORMAT]",
+    )?;
+
+    format
+        .first()
+        .and_then(|f| f.get("duration"))
+        .and_then(|d| d.parse::<f64>().ok())
+        .ok_or_else(|| io::Error::other("failed to parse the duration"))
+}
+
+/// Runs ffmpeg over the file, with `filter` being a filtergraph that produces
+/// the `[v]` stream of frames at the target dimensions.
 fn process_video_file<F, T>(
-    video_file: &PathBuf,
-    target_width: u32,
-    target_height: u32,
+    video_file: &Path,
+    trim: Trim,
+    filter: &str,
     writer: &mut T,
     mut handle_output: F,
 ) -> io::Result<()>
 where
     F: FnMut(&mut T, &mut std::process::ChildStdout) -> io::Result<()>
 {
-    let mut child = Command::new("ffmpeg")
-        .arg("-hide_banner")
+    let mut command = Command::new("ffmpeg");
+    command.arg("-hide_banner");
+    trim.add_input_args(&mut command);
+
+    let mut child = command
         .arg("-i")
-        .arg(&format!("{}", video_file.display()))
-        .arg("-vf")
-        .arg(&format!("scale={}:{}", target_width, target_height))
+        .arg(format!("{}", video_file.display()))
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("[v]")
         .arg("-f")
         .arg("rawvideo")
         .arg("-pix_fmt")
@@ -121,19 +231,13 @@ where
     }
 
     else {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "cannot produce stdout",
-        ));
+        return Err(io::Error::other("cannot produce stdout"));
     }
 
     let ecode = child.wait()?;
 
     if !ecode.success() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "ffmpeg command failed",
-        ));
+        return Err(io::Error::other("ffmpeg command failed"));
     }
 
     Ok(retval)
@@ -144,7 +248,7 @@ fn process_video_stream<T>(
     target_height: u32,
     mut stream: impl Read,
     writer: &mut T, 
-    mut per_file: impl FnMut(&mut T, &Buffer),
+    mut per_file: impl FnMut(&mut T, &Buffer) -> io::Result<()>,
 ) -> io::Result<()> {
     let frame_pixels = target_width * target_height;
     let frame_bytes = (frame_pixels * 4) as usize;
@@ -189,15 +293,8 @@ fn process_video_stream<T>(
             }
         }
     } {
-        let image =
-            ImageBuffer::from_raw(target_width, target_height, bytes).unwrap();
-        let image = image::DynamicImage::ImageBgra8(image);
-
-        output_buffer.clear();
-        AsciiBuilder::new_from_image(image)
-            .set_deep(true) // what if you used false?
-            .to_stream_colored(&mut output_buffer);
-        per_file(writer, &output_buffer);
+        render_frame(target_width, target_height, bytes, &mut output_buffer);
+        per_file(writer, &output_buffer)?;
 
         bytes = vec![0u8; frame_bytes];
     }
@@ -205,6 +302,22 @@ fn process_video_stream<T>(
     Ok(())
 }
 
+/// Turns a frame of BGRA pixels into colored characters, a line per row.
+fn render_frame(
+    width: u32,
+    height: u32,
+    bytes: Vec<u8>,
+    output: &mut Buffer,
+) {
+    let image = ImageBuffer::from_raw(width, height, bytes).unwrap();
+    let image = image::DynamicImage::ImageBgra8(image);
+
+    output.clear();
+    AsciiBuilder::new_from_image(image)
+        .set_deep(true) // what if you used false?
+        .to_stream_colored(output);
+}
+
 fn new_target_dimensions(
     src_width: u32,
     src_height: u32,
@@ -240,23 +353,36 @@ fn get_char_dims(char_string: Option<String>) -> Result<(u32, u32), &'static str
     let mut iter = char_string.split("x");
     let width = iter
         .next()
-        .ok_or_else(|| "string is empty")?
+        .ok_or("string is empty")?
         .parse::<u32>()
         .map_err(|_| "cannot convert to u32")?;
     let height = iter
         .next()
-        .ok_or_else(|| "string is empty")?
+        .ok_or("string is empty")?
         .parse::<u32>()
         .map_err(|_| "input doesn't have height")?;
     
     Ok((width, height))
 }
 
-#[derive(Debug, Default)]
-struct MovieInProgress {
-    starting: String,
-    current: String,
-    frame_diffs: Vec<Vec<diff::Result<char>>>,
+/// Reads the character dimensions out of a calibration file of the player,
+/// which has them in pixels as `<width> <height>`. Only their ratio matters,
+/// which is kept to a tenth of a pixel.
+fn read_calibration(path: &Path) -> io::Result<(u32, u32)> {
+    let calibration = std::fs::read_to_string(path)?;
+    let invalid = || Error::other("the calibration isn't <width> <height>");
+
+    let mut dimensions = calibration.split_whitespace().map(|d| {
+        d.parse::<f64>()
+            .ok()
+            .filter(|d| d.is_finite() && 0. < *d)
+            .map(|d| (d * 10.).round() as u32)
+    });
+
+    let width = dimensions.next().flatten().ok_or_else(invalid)?;
+    let height = dimensions.next().flatten().ok_or_else(invalid)?;
+
+    Ok((width, height))
 }
 
 #[derive(Parser)]
@@ -268,6 +394,123 @@ pub struct Args {
     target_height: Option<u32>,
     #[clap(long)]
     char_dims: Option<String>,
+    /// Take the character dimensions from the calibration file written by
+    /// `--calibrate` of the player, rather than from --char-dims
+    #[clap(long, conflicts_with = "char_dims")]
+    calibration: Option<PathBuf>,
+    /// Embed the soundtrack of the video into the output
+    #[clap(long)]
+    audio: bool,
+    /// Bitrate of the embedded soundtrack, as understood by ffmpeg
+    #[clap(long, default_value = "48k")]
+    audio_bitrate: String,
+    /// Embed the soundtrack as a synthesized monophonic tune, which is a
+    /// fraction of the size of the real audio
+    #[clap(long, conflicts_with = "audio")]
+    chiptune: bool,
+    /// Render a visualization of the soundtrack as the video of an audio
+    /// file, instead of making a radio file
+    #[clap(long, value_enum)]
+    visualizer: Option<Visualizer>,
+    /// Where to start encoding from, in seconds
+    #[clap(long, default_value_t = 0.)]
+    start: f64,
+    /// How much to encode, in seconds
+    #[clap(long)]
+    duration: Option<f64>,
+    /// Delay the soundtrack against the video by this many milliseconds, or
+    /// make it play earlier with a negative value
+    #[clap(long, default_value_t = 0, allow_negative_numbers = true)]
+    audio_offset: i64,
+    /// Which audio stream to use as the soundtrack, counting from 0
+    #[clap(long, default_value_t = 0)]
+    audio_track: usize,
+    /// List the audio streams that --audio-track can pick from, then exit
+    #[clap(long)]
+    list_tracks: bool,
+    /// Print what's in the movie given in place of the video, as the player
+    /// reads it, then exit
+    #[clap(long)]
+    probe: bool,
+    /// Play the movie given in place of the video in the terminal, as the
+    /// player reads it, then exit
+    #[clap(long)]
+    preview: bool,
+    /// Push the movie given in place of the video into the game through its
+    /// Remote API, as the base64 that the player reads, then exit
+    #[clap(long)]
+    upload: bool,
+    /// The name of the movie in the game, for --upload. Its own name with
+    /// .txt if not given
+    #[clap(long, requires = "upload")]
+    upload_as: Option<String>,
+    /// Push this file along with the movie, like the player or remote.js.
+    /// Can be given more than once
+    #[clap(long, requires = "upload")]
+    upload_file: Vec<PathBuf>,
+    /// The server of the game that --upload pushes onto
+    #[clap(long, default_value = "home")]
+    upload_server: String,
+    /// The port that the game connects to for --upload, as set under
+    /// Options > Remote API
+    #[clap(long, default_value_t = bitburner_remote::DEFAULT_PORT)]
+    upload_port: u16,
+    /// Embed the subtitles of an SRT file, optionally tagged with a language
+    /// as `en=movie.en.srt`. Can be given once per language
+    #[clap(long)]
+    subs: Vec<String>,
+    /// Embed every subtitle stream of the video
+    #[clap(long, conflicts_with = "subs")]
+    embedded_subs: bool,
+    /// Paint the first subtitle track into the frames instead of embedding
+    /// the subtitles
+    #[clap(long)]
+    burn_subs: bool,
+    /// The color of burned-in subtitles
+    #[clap(long, value_enum, default_value = "white")]
+    subs_color: subtitles::Color,
+    /// Where burned-in subtitles go
+    #[clap(long, value_enum, default_value = "bottom")]
+    subs_position: subtitles::Position,
+    /// Wrap burned-in subtitles at this many characters
+    #[clap(long)]
+    subs_width: Option<usize>,
+    /// Embed the chapters of the video, with a thumbnail of each for the
+    /// chapter strip of the player
+    #[clap(long)]
+    chapters: bool,
+    /// The width of the chapter thumbnails, in characters
+    #[clap(long, default_value_t = 16, requires = "chapters")]
+    chapter_thumb_width: u32,
+    /// Embed the word-timed lyrics of an enhanced LRC file, for the player to
+    /// show karaoke style
+    #[clap(long)]
+    lyrics: Option<PathBuf>,
+    /// The title shown by the info panel of the player. Taken from the tags
+    /// of the file if not given
+    #[clap(long)]
+    title: Option<String>,
+    /// Who made the video, for the info panel. Taken from the tags of the
+    /// file if not given
+    #[clap(long)]
+    author: Option<String>,
+    /// Where the video came from, for the info panel
+    #[clap(long)]
+    source_url: Option<String>,
+    /// The credit or license of the video, which the player shows when the
+    /// movie starts. Taken from the copyright tag of the file if not given
+    #[clap(long)]
+    attribution: Option<String>,
+    /// Transcribe the soundtrack into a subtitle track with the whisper model
+    /// at this path
+    #[cfg(feature = "auto-subs")]
+    #[clap(long)]
+    auto_subs: Option<PathBuf>,
+    /// The language spoken in the soundtrack, for --auto-subs. Detected if
+    /// not given
+    #[cfg(feature = "auto-subs")]
+    #[clap(long, requires = "auto_subs")]
+    auto_subs_lang: Option<String>,
 }
 
 fn main() {
@@ -275,50 +518,289 @@ fn main() {
 
     let args = Args::parse();
 
-    if args.target_width == Some(0) {
-        panic!("target_width cannot be zero");
+    if args.probe {
+        probe::probe(&args.video).unwrap();
+        return;
     }
 
-    if args.target_height == Some(0) {
-        panic!("target_height cannot be zero");
+    if args.preview {
+        probe::preview(&args.video).unwrap();
+        return;
     }
 
-    if args.target_width.is_none() && args.target_height.is_none() {
-        panic!("must set either target_width or target_height")
+    if args.upload {
+        upload::upload(
+            &args.video,
+            args.upload_as.as_deref(),
+            &args.upload_file,
+            &args.upload_server,
+            args.upload_port,
+        )
+        .unwrap();
+        return;
     }
 
-    let (char_width, char_height) = get_char_dims(args.char_dims).unwrap();
+    if args.list_tracks {
+        let tracks = audio::list_tracks(&args.video).unwrap();
+
+        for (number, track) in tracks.iter().enumerate() {
+            let title = track
+                .title
+                .as_ref()
+                .map(|t| format!(" \"{}\"", t))
+                .unwrap_or_default();
+
+            println!(
+                "{}: {}{} ({}, {} channels)",
+                number,
+                track.language.as_deref().unwrap_or("und"),
+                title,
+                track.codec,
+                track.channels,
+            );
+        }
+
+        return;
+    }
 
-    let (width, height) = get_video_dimensions(&args.video).unwrap();
-    let (target_width, target_height) = new_target_dimensions(
-        width,
-        height,
-        char_width, char_height,
-        args.target_width,
-        args.target_height,
-    );
+    let has_video = has_video_stream(&args.video).unwrap();
 
-    let framerate = get_video_fps(&args.video).unwrap();
+    if has_video && args.visualizer.is_some() {
+        panic!("visualizer can only be used on audio files");
+    }
+
+    if !has_video && args.chapters {
+        panic!("chapters can only be used on video files");
+    }
+
+    let has_frames = has_video || args.visualizer.is_some();
+
+    let trim = Trim {
+        start: args.start,
+        duration: args.duration,
+    };
+    let audio_input = audio::AudioInput {
+        file: &args.video,
+        trim,
+        offset_ms: args.audio_offset,
+        track: args.audio_track,
+    };
+
+    // audio files without a visualizer become radio files: a soundtrack with
+    // a zero-sized video that has no frames
+    let (framerate, target_width, target_height) = if has_frames {
+        if args.target_width == Some(0) {
+            panic!("target_width cannot be zero");
+        }
+
+        if args.target_height == Some(0) {
+            panic!("target_height cannot be zero");
+        }
+
+        if args.target_width.is_none() && args.target_height.is_none() {
+            panic!("must set either target_width or target_height")
+        }
+
+        let (char_width, char_height) = match &args.calibration {
+            Some(calibration) => read_calibration(calibration).unwrap(),
+            None => get_char_dims(args.char_dims).unwrap(),
+        };
+
+        let (width, height) = match has_video {
+            true => get_video_dimensions(&args.video).unwrap(),
+            false => visualizer::SOURCE_SIZE,
+        };
+        let (target_width, target_height) = new_target_dimensions(
+            width,
+            height,
+            char_width, char_height,
+            args.target_width,
+            args.target_height,
+        );
+
+        let framerate = match has_video {
+            true => get_video_fps(&args.video).unwrap(),
+            false => visualizer::FRAMERATE as f64,
+        };
+
+        (framerate, target_width, target_height)
+    }
+
+    else {
+        (0., 0, 0)
+    };
 
     let mut encoder = lz4::EncoderBuilder::new().level(9).build(std::io::stdout().lock()).unwrap();
 
-    writeln!(&mut encoder, "{}", framerate);
-    writeln!(&mut encoder, "{} {}", target_width, target_height);
+    writeln!(&mut encoder, "{}", framerate).unwrap();
+
+    // the frame count is only an estimate, good enough for the progress bar
+    // of the player, since it has to be written ahead of the frames
+    match has_frames {
+        true => {
+            let duration = trim.length_of(get_duration(&args.video).unwrap());
+            let frames = (duration * framerate).round() as u64;
+
+            writeln!(&mut encoder, "{} {} {}", target_width, target_height, frames)
+                .unwrap();
+        },
+        false => {
+            writeln!(&mut encoder, "{} {}", target_width, target_height).unwrap();
+        },
+    }
 
-    let per_string = move |encoder: &mut lz4::Encoder<_>, s: &Buffer| {
-        encoder.write(s.as_slice());
+    // the soundtrack is all there is to an audio file, so it's implied
+    if args.audio || (!has_video && !args.chiptune) {
+        let audio = audio::extract_audio(&audio_input, &args.audio_bitrate)
+            .unwrap();
+        container::write_chunk(&mut encoder, container::AUDIO_TAG, &audio)
+            .unwrap();
+    }
+
+    if args.chiptune {
+        let tune = chiptune::extract_chiptune(&audio_input).unwrap();
+        container::write_chunk(&mut encoder, container::TUNE_TAG, &tune)
+            .unwrap();
+    }
+
+    let mut tracks = match args.embedded_subs {
+        true => subtitles::extract_all_subtitles(&args.video).unwrap(),
+        false => args
+            .subs
+            .iter()
+            .map(|s| subtitles::read_subs_arg(s).unwrap())
+            .collect(),
+    };
+
+    for track in tracks.iter_mut() {
+        track.cues = subtitles::apply_trim(std::mem::take(&mut track.cues), trim);
+    }
+
+    // transcribing the trimmed soundtrack makes for cues that need no trim
+    #[cfg(feature = "auto-subs")]
+    if let Some(model) = args.auto_subs.as_ref() {
+        let language = args.auto_subs_lang.as_deref();
+        tracks.push(whisper::transcribe(&audio_input, model, language).unwrap());
+    }
+
+    if args.burn_subs && tracks.is_empty() {
+        panic!("burn_subs needs either subs or embedded_subs");
+    }
+
+    let burned_cues = match args.burn_subs {
+        true => tracks.into_iter().next().map(|t| t.cues),
+        false => {
+            for track in tracks.iter() {
+                container::write_chunk(
+                    &mut encoder,
+                    container::SUBTITLES_TAG,
+                    &subtitles::chunk_payload(track),
+                )
+                .unwrap();
+            }
+
+            None
+        },
+    };
+
+    if args.chapters && args.chapter_thumb_width == 0 {
+        panic!("chapter_thumb_width cannot be zero");
+    }
+
+    if args.chapters {
+        let chapters = chapters::list_chapters(&args.video).unwrap();
+        let chapters = chapters::apply_trim(chapters, trim);
+
+        // the thumbnails keep the shape of the frames
+        let thumbnail_size = (
+            args.chapter_thumb_width,
+            (target_height * args.chapter_thumb_width / target_width).max(1),
+        );
+
+        let payload =
+            chapters::chunk_payload(&args.video, &chapters, trim, thumbnail_size)
+                .unwrap();
+        container::write_chunk(&mut encoder, container::CHAPTERS_TAG, &payload)
+            .unwrap();
+    }
+
+    if let Some(path) = args.lyrics.as_ref() {
+        let lines = lyrics::apply_trim(lyrics::read_lrc(path).unwrap(), trim);
+        container::write_chunk(
+            &mut encoder,
+            container::LYRICS_TAG,
+            &lyrics::chunk_payload(&lines),
+        )
+        .unwrap();
+    }
+
+    let metadata = metadata::Metadata {
+        title: args.title.clone(),
+        author: args.author.clone(),
+        source_url: args.source_url.clone(),
+        attribution: args.attribution.clone(),
+    }
+    .or(metadata::read_tags(&args.video).unwrap());
+
+    if !metadata.is_empty() {
+        container::write_chunk(
+            &mut encoder,
+            container::METADATA_TAG,
+            &metadata::chunk_payload(&metadata),
+        )
+        .unwrap();
+    }
+
+    let subs_style = subtitles::Style {
+        color: args.subs_color,
+        position: args.subs_position,
+        max_width: args.subs_width,
+    };
+
+    let mut frame_index = 0;
+    let mut per_string = move |encoder: &mut lz4::Encoder<_>, s: &Buffer| {
+        let time_ms = (frame_index as f64 * 1000. / framerate) as u64;
+        frame_index += 1;
+
+        let cue = burned_cues
+            .as_deref()
+            .and_then(|c| subtitles::active(c, time_ms));
+
+        match cue {
+            Some(cue) => {
+                let frame = String::from_utf8_lossy(s.as_slice());
+                let frame = subtitles::burn(
+                    &frame,
+                    cue,
+                    target_width as usize,
+                    subs_style,
+                );
+                encoder.write_all(frame.as_bytes())
+            },
+            None => encoder.write_all(s.as_slice()),
+        }
     };
 
-    let movie = process_video_file(&args.video, target_width, target_height, &mut encoder, |w, r| {
-        process_video_stream(target_width, target_height, r, w, per_string)
-    })
-    .unwrap();
+    if has_frames {
+        let filter = match args.visualizer {
+            Some(visualizer) => {
+                visualizer.filter(args.audio_track, target_width, target_height)
+            },
+            None => format!("[0:v:0]scale={}:{}[v]", target_width, target_height),
+        };
+
+        process_video_file(&args.video, trim, &filter, &mut encoder, |w, r| {
+            process_video_stream(target_width, target_height, r, w, &mut per_string)
+        })
+        .unwrap();
+    }
 
     // format:
     // - framerate
     // - dimensions
-    // - audio (TODO)
+    // - chunks (audio, chiptune, subtitles, chapters, lyrics, metadata)
     // - video
 
-    encoder.flush();
+    let (_, result) = encoder.finish();
+    result.unwrap();
 }
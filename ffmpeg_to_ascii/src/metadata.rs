@@ -0,0 +1,89 @@
+//! The credits of the movie, for the info panel of the player.
+
+use std::{
+    io,
+    path::Path,
+};
+
+use crate::probe_sections;
+
+#[derive(Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub source_url: Option<String>,
+    /// The credit or license that the player shows when the movie starts.
+    pub attribution: Option<String>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.author.is_none()
+            && self.source_url.is_none()
+            && self.attribution.is_none()
+    }
+
+    /// Fills in what's missing from `other`.
+    pub fn or(
+        self,
+        other: Metadata,
+    ) -> Metadata {
+        Metadata {
+            title: self.title.or(other.title),
+            author: self.author.or(other.author),
+            source_url: self.source_url.or(other.source_url),
+            attribution: self.attribution.or(other.attribution),
+        }
+    }
+}
+
+/// Reads the credits out of the tags of the file. Containers don't agree on
+/// the case of the tags, or on what the author is called.
+pub fn read_tags(file: &Path) -> io::Result<Metadata> {
+    let sections =
+        probe_sections(file, &["-show_entries", "format_tags"], "[FORMAT]")?;
+
+    let Some(tags) = sections.into_iter().next()
+    else {
+        return Ok(Metadata::default());
+    };
+
+    let tags = tags
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let key = key.strip_prefix("TAG:")?.to_ascii_lowercase();
+            let value = value.trim().replace(['\r', '\n'], " ");
+            (!value.is_empty()).then_some((key, value))
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let find = |keys: &[&str]| keys.iter().find_map(|k| tags.get(*k).cloned());
+
+    Ok(Metadata {
+        title: find(&["title"]),
+        author: find(&["artist", "author", "album_artist", "composer"]),
+        source_url: find(&["purl", "url"]),
+        attribution: find(&["copyright", "license"]),
+    })
+}
+
+/// Creates the payload of a metadata chunk.
+pub fn chunk_payload(metadata: &Metadata) -> Vec<u8> {
+    let fields = [
+        ("title", &metadata.title),
+        ("author", &metadata.author),
+        ("source", &metadata.source_url),
+        ("attribution", &metadata.attribution),
+    ];
+
+    let mut payload = String::new();
+
+    for (key, value) in fields {
+        if let Some(value) = value {
+            payload += &format!("{}={}\n", key, value.replace('\n', " "));
+        }
+    }
+
+    payload.into_bytes()
+}
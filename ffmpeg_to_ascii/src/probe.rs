@@ -0,0 +1,147 @@
+//! Looking into a movie written by this tool, for `--probe`, and playing it
+//! in the terminal, for `--preview`, through the same reading of the
+//! container as that of the player.
+
+use std::{
+    fs,
+    io::{
+        self,
+        BufReader,
+        Write,
+    },
+    path::Path,
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use base64::engine::Engine as _;
+use bitburner_video::{
+    self as movie,
+    Decoded,
+    Decompressor,
+};
+
+/// The movie at `path` as the encoder compressed it, which the file can have
+/// the base64 of instead.
+fn read_compressed(path: &Path) -> io::Result<Vec<u8>> {
+    let contents = fs::read(path)?;
+
+    match Decompressor::new(&contents[..]) {
+        Ok(_) => Ok(contents),
+        Err(_) => {
+            base64::prelude::BASE64_STANDARD
+                .decode(contents.trim_ascii())
+                .map_err(io::Error::other)
+        },
+    }
+}
+
+/// Prints the header, the chunks, and the frames of the movie at `path`,
+/// which can be the output of the encoder as it is or the base64 of it.
+pub fn probe(path: &Path) -> io::Result<()> {
+    let compressed = read_compressed(path)?;
+
+    let mut reader = BufReader::new(Decompressor::new(&compressed[..])?);
+    let header = movie::read_header(&mut reader)?;
+
+    match header.is_radio() {
+        true => println!("a radio file"),
+        false => {
+            println!(
+                "{}x{} at {} fps",
+                header.width, header.height, header.framerate,
+            )
+        },
+    }
+
+    for chunk in movie::read_chunks(&mut reader)? {
+        println!("#{} chunk of {} bytes", chunk.tag, chunk.payload.len());
+    }
+
+    if header.is_radio() {
+        return Ok(());
+    }
+
+    let mut frames = movie::Frames::new(reader, header.width, header.height);
+    frames.check_first()?;
+
+    let mut frame = String::new();
+    let mut count = 0;
+    let mut corrupt = vec![];
+
+    loop {
+        frame.clear();
+
+        match frames.read_frame(&mut frame)? {
+            Decoded::Whole => {},
+            Decoded::Corrupt => corrupt.push(count),
+            Decoded::End => break,
+        }
+
+        count += 1;
+    }
+
+    match header.frames {
+        Some(estimate) => println!("{} frames, estimated {}", count, estimate),
+        None => println!("{} frames", count),
+    }
+
+    if !corrupt.is_empty() {
+        println!("corrupt frames: {:?}", corrupt);
+    }
+
+    Ok(header.check_count(count)?)
+}
+
+/// Plays the movie at `path` in the terminal at its framerate, with the frame
+/// before standing in for a corrupt one, as the player does.
+pub fn preview(path: &Path) -> io::Result<()> {
+    let compressed = read_compressed(path)?;
+
+    let mut reader = BufReader::new(Decompressor::new(&compressed[..])?);
+    let header = movie::read_header(&mut reader)?;
+    movie::read_chunks(&mut reader)?;
+
+    if header.is_radio() {
+        return Err(io::Error::other("a radio file has no frames to preview"));
+    }
+
+    let mut frames = movie::Frames::new(reader, header.width, header.height);
+    frames.check_first()?;
+
+    let frame_time = Duration::from_secs_f64(1. / header.framerate);
+    let started = Instant::now();
+    let mut stdout = io::stdout().lock();
+    let mut frame = String::new();
+    let mut last_frame = String::new();
+    let mut count = 0;
+
+    // the frames go up over one another, from the top left
+    write!(stdout, "\x1b[2J")?;
+
+    loop {
+        frame.clear();
+
+        match frames.read_frame(&mut frame)? {
+            Decoded::Whole => {},
+            Decoded::Corrupt => frame += &last_frame,
+            Decoded::End => break,
+        }
+
+        // every frame is due at a point of the timeline, so the time that a
+        // sleep overshoots by doesn't add up over the frames
+        let due = started + frame_time * count;
+        thread::sleep(due.saturating_duration_since(Instant::now()));
+
+        write!(stdout, "\x1b[H{}\x1b[0m", frame)?;
+        stdout.flush()?;
+
+        std::mem::swap(&mut frame, &mut last_frame);
+        count += 1;
+    }
+
+    Ok(header.check_count(count as usize)?)
+}
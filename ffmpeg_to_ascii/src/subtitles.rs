@@ -0,0 +1,425 @@
+//! Timed subtitle cues, from SRT files or the subtitle streams of the video.
+
+use std::{
+    fs,
+    io,
+    path::Path,
+    process::Command,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    probe_streams,
+    trim::Trim,
+};
+
+/// The language of tracks that don't say what they are.
+const UNDETERMINED: &str = "und";
+
+pub struct Cue {
+    /// When the cue shows up and goes away, in milliseconds.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub lines: Vec<String>,
+}
+
+pub struct Track {
+    pub language: String,
+    pub cues: Vec<Cue>,
+}
+
+/// Parses an SRT timestamp, `hh:mm:ss,mmm`, into milliseconds.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (time, millis) = timestamp.trim().split_once([',', '.'])?;
+    let mut parts = time.split(':').map(|p| p.parse::<u64>().ok());
+    let (hours, minutes, secs) =
+        (parts.next()??, parts.next()??, parts.next()??);
+
+    Some(
+        ((hours * 60 + minutes) * 60 + secs) * 1000
+            + millis.parse::<u64>().ok()?,
+    )
+}
+
+/// Removes the HTML-like tags (`<i>`) and positioning codes (`{\an8}`) that
+/// SRT files tend to have, since there's no way of showing them.
+fn strip_tags(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut closing = None;
+
+    for c in line.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (None, c) => stripped.push(c),
+            (Some(end), c) if end == c => closing = None,
+            (Some(_), _) => {},
+        }
+    }
+
+    stripped
+}
+
+pub fn parse_srt(srt: &str) -> Vec<Cue> {
+    let mut cues = vec![];
+    let srt = srt.replace("\r\n", "\n");
+
+    for block in srt.split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+
+        let Some((start, end)) = lines.next().and_then(|l| l.split_once("-->"))
+        else {
+            continue;
+        };
+
+        // the end timestamp can be followed by positioning
+        let end = end.split_whitespace().next().unwrap_or_default();
+
+        let (Some(start_ms), Some(end_ms)) =
+            (parse_timestamp(start), parse_timestamp(end))
+        else {
+            continue;
+        };
+
+        let lines = lines
+            .map(strip_tags)
+            .map(|l| l.trim().to_owned())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>();
+
+        if !lines.is_empty() {
+            cues.push(Cue {
+                start_ms,
+                end_ms,
+                lines,
+            });
+        }
+    }
+
+    cues.sort_by_key(|c| c.start_ms);
+    cues
+}
+
+/// Reads the track out of a `--subs` argument, which is either the path to the
+/// SRT file or a `<language>=<path>` pair.
+pub fn read_subs_arg(arg: &str) -> io::Result<Track> {
+    let (language, path) = match arg.split_once('=') {
+        Some((language, path))
+            if !language.is_empty()
+                && language.len() <= 8
+                && language
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+        {
+            (language, path)
+        },
+        _ => (UNDETERMINED, arg),
+    };
+
+    Ok(Track {
+        language: language.to_owned(),
+        cues: parse_srt(&fs::read_to_string(path)?),
+    })
+}
+
+/// Extracts every subtitle stream of the video along with their language.
+pub fn extract_all_subtitles(video_file: &Path) -> io::Result<Vec<Track>> {
+    let streams = probe_streams(video_file, "s", "stream_tags=language")?;
+
+    streams
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut stream)| {
+            Ok(Track {
+                language: stream
+                    .remove("TAG:language")
+                    .unwrap_or_else(|| UNDETERMINED.to_owned()),
+                cues: extract_subtitles(video_file, index)?,
+            })
+        })
+        .collect()
+}
+
+/// Extracts subtitle stream `index` of the video, which ffmpeg converts into
+/// SRT for us.
+fn extract_subtitles(
+    video_file: &Path,
+    index: usize,
+) -> io::Result<Vec<Cue>> {
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-i")
+        .arg(format!("{}", video_file.display()))
+        .arg("-map")
+        .arg(format!("0:s:{}", index))
+        .arg("-f")
+        .arg("srt")
+        .arg("-")
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other("ffmpeg could not extract the subtitles"));
+    }
+
+    Ok(parse_srt(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Makes the cues relative to the start of the trim, dropping the ones that
+/// fall outside of it.
+pub fn apply_trim(
+    cues: Vec<Cue>,
+    trim: Trim,
+) -> Vec<Cue> {
+    let start = (trim.start * 1000.) as u64;
+    let end = trim.duration.map(|d| start + (d * 1000.) as u64);
+
+    cues.into_iter()
+        .filter(|c| start < c.end_ms && end.is_none_or(|end| c.start_ms < end))
+        .map(|c| Cue {
+            start_ms: c.start_ms.saturating_sub(start),
+            end_ms: c.end_ms - start,
+            lines: c.lines,
+        })
+        .collect()
+}
+
+/// Writes the cues the way the chunks with timed text hold them: separated by
+/// blank lines, each a `<start> <end>` line followed by the lines of text.
+pub fn write_cues(cues: &[Cue]) -> String {
+    let mut payload = String::new();
+
+    for cue in cues.iter() {
+        payload += &format!("{} {}\n", cue.start_ms, cue.end_ms);
+
+        for line in cue.lines.iter() {
+            payload += line;
+            payload += "\n";
+        }
+
+        payload += "\n";
+    }
+
+    payload
+}
+
+/// Creates the payload of a subtitle chunk.
+pub fn chunk_payload(track: &Track) -> Vec<u8> {
+    format!("{}\n{}", track.language, write_cues(&track.cues)).into_bytes()
+}
+
+/// The cue to show at `time_ms` into the movie, if any.
+pub fn active(
+    cues: &[Cue],
+    time_ms: u64,
+) -> Option<&Cue> {
+    // of the cues that have started, the latest one that hasn't ended
+    let started = cues.partition_point(|c| c.start_ms <= time_ms);
+    cues[.. started].iter().rev().find(|c| time_ms < c.end_ms)
+}
+
+/// Replaces `columns` characters of an ANSI-colored frame row, starting at
+/// column `start`, with `replacement`. Every character of a frame comes with
+/// its own color, so the rest of the row keeps its colors.
+fn splice_row(
+    row: &str,
+    start: usize,
+    columns: usize,
+    replacement: &str,
+) -> String {
+    let mut output = String::with_capacity(row.len() + replacement.len());
+    let mut column = 0;
+    let mut chars = row.chars();
+
+    while let Some(c) = chars.next() {
+        let skipping = start <= column && column < start + columns;
+
+        if c == '\u{1b}' {
+            // copy or drop the whole escape sequence, up to its final letter
+            let mut sequence = String::from(c);
+            for c in chars.by_ref() {
+                sequence.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+
+            if !skipping {
+                output += &sequence;
+            }
+
+            continue;
+        }
+
+        if column == start {
+            output += replacement;
+        }
+
+        if !skipping {
+            output.push(c);
+        }
+
+        column += 1;
+    }
+
+    if column <= start {
+        output += replacement;
+    }
+
+    output
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Position {
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Color {
+    White,
+    Yellow,
+    Cyan,
+    Green,
+    Magenta,
+    Red,
+    Blue,
+}
+
+impl Color {
+    /// The SGR code of the bright variant of the color.
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::White => 97,
+            Color::Yellow => 93,
+            Color::Cyan => 96,
+            Color::Green => 92,
+            Color::Magenta => 95,
+            Color::Red => 91,
+            Color::Blue => 94,
+        }
+    }
+}
+
+/// How burned-in subtitles look.
+#[derive(Clone, Copy)]
+pub struct Style {
+    pub color: Color,
+    pub position: Position,
+    /// The widest that a line can get before it wraps, in characters. The
+    /// width of the frame is the limit either way.
+    pub max_width: Option<usize>,
+}
+
+/// Word-wraps the lines to the width. Words that are wider than that on their
+/// own get split.
+pub fn wrap(
+    lines: &[String],
+    width: usize,
+) -> Vec<String> {
+    let width = width.max(1);
+    let mut wrapped = vec![];
+
+    for line in lines {
+        let mut current = String::new();
+        let mut current_width = 0;
+
+        for word in line.split_whitespace() {
+            let mut word = word.chars().collect::<Vec<_>>();
+
+            while !word.is_empty() {
+                let separator = usize::from(current_width != 0);
+
+                if current_width + separator + word.len() <= width {
+                    if separator != 0 {
+                        current.push(' ');
+                    }
+
+                    current.extend(word.iter());
+                    current_width += separator + word.len();
+                    word.clear();
+                }
+                else if current_width != 0 {
+                    wrapped.push(std::mem::take(&mut current));
+                    current_width = 0;
+                }
+                else {
+                    let rest = word.split_off(width);
+                    wrapped.push(word.iter().collect());
+                    word = rest;
+                }
+            }
+        }
+
+        if current_width != 0 {
+            wrapped.push(current);
+        }
+    }
+
+    wrapped
+}
+
+/// Paints the cue over the top or bottom rows of the frame: bold text in a
+/// black box with a character of margin, which stays readable over any
+/// video.
+pub fn burn(
+    frame: &str,
+    cue: &Cue,
+    width: usize,
+    style: Style,
+) -> String {
+    const RESET: &str = "\u{1b}[0m";
+
+    let text_style = format!("\u{1b}[0;1;{};40m", style.color.ansi_code());
+
+    let max_width = width.saturating_sub(2);
+    let max_width = style.max_width.map_or(max_width, |w| w.min(max_width));
+    let lines = wrap(&cue.lines, max_width);
+
+    let text_width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let box_width = (text_width + 2).min(width);
+    let box_start = (width - box_width) / 2;
+
+    // a blank row above and below the text
+    let mut box_rows = vec![String::new()];
+    box_rows.extend(lines);
+    box_rows.push(String::new());
+
+    let rows = frame.lines().collect::<Vec<_>>();
+    let first_box_row = match style.position {
+        Position::Top => 0,
+        Position::Bottom => rows.len().saturating_sub(box_rows.len()),
+    };
+
+    let mut output = String::with_capacity(frame.len());
+
+    for (index, row) in rows.iter().enumerate() {
+        let text = index
+            .checked_sub(first_box_row)
+            .and_then(|box_row| box_rows.get(box_row));
+
+        match text {
+            Some(text) => {
+                let padding = box_width - text.chars().count();
+                let left = padding / 2;
+                let replacement = format!(
+                    "{}{}{}{}{}",
+                    text_style,
+                    " ".repeat(left),
+                    text,
+                    " ".repeat(padding - left),
+                    RESET,
+                );
+
+                output += &splice_row(row, box_start, box_width, &replacement);
+            },
+            None => output += row,
+        }
+
+        output += "\n";
+    }
+
+    output
+}
@@ -0,0 +1,34 @@
+use std::process::Command;
+
+/// The part of the input that gets encoded, in seconds.
+#[derive(Clone, Copy, Default)]
+pub struct Trim {
+    pub start: f64,
+    pub duration: Option<f64>,
+}
+
+impl Trim {
+    /// How much of an input of `length` seconds is left after trimming it.
+    pub fn length_of(
+        &self,
+        length: f64,
+    ) -> f64 {
+        let left = (length - self.start).max(0.);
+        self.duration.map_or(left, |d| left.min(d))
+    }
+
+    /// Adds the options that seek to the start and stop after the duration.
+    /// These apply to the next input, so they must come before its `-i`.
+    pub fn add_input_args(
+        &self,
+        command: &mut Command,
+    ) {
+        if 0. < self.start {
+            command.arg("-ss").arg(self.start.to_string());
+        }
+
+        if let Some(duration) = self.duration {
+            command.arg("-t").arg(duration.to_string());
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Pushing a movie into the game, for `--upload`, as the base64 that the
+//! player reads, along with whatever else is to go with it.
+
+use std::{
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use base64::engine::Engine as _;
+use bitburner_remote::File;
+use bitburner_video::Decompressor;
+
+/// The name of the file in the game, which only takes text files by some
+/// extensions, `.txt` being one of them.
+fn game_filename(path: &Path) -> io::Result<String> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| io::Error::other("the path has no name to go by"))?;
+
+    Ok(match name.ends_with(".txt") || name.ends_with(".js") {
+        true => name.to_owned(),
+        false => format!("{}.txt", name),
+    })
+}
+
+/// The text of the file, or the base64 of it if it isn't text, like the
+/// output of the encoder as it is, or a `.wasm`.
+fn read_as_text(path: &Path) -> io::Result<String> {
+    let contents = fs::read(path)?;
+
+    // the compressed movie could pass for text, by chance
+    if Decompressor::new(&contents[..]).is_ok() {
+        return Ok(base64::prelude::BASE64_STANDARD.encode(contents));
+    }
+
+    String::from_utf8(contents)
+        .or_else(|e| Ok(base64::prelude::BASE64_STANDARD.encode(e.as_bytes())))
+}
+
+/// Pushes the movie at `movie` onto `server`, named `name`, and the others
+/// after it by their own names, waiting on the game to connect on `port`.
+pub fn upload(
+    movie: &Path,
+    name: Option<&str>,
+    others: &[PathBuf],
+    server: &str,
+    port: u16,
+) -> io::Result<()> {
+    let mut files = vec![File {
+        filename: match name {
+            Some(name) => name.to_owned(),
+            None => game_filename(movie)?,
+        },
+        content: read_as_text(movie)?,
+    }];
+
+    for path in others {
+        files.push(File {
+            filename: game_filename(path)?,
+            content: read_as_text(path)?,
+        });
+    }
+
+    bitburner_remote::push_files(port, server, &files)
+}
@@ -0,0 +1,43 @@
+//! Audio-reactive visualizations, used as the video of audio files.
+
+use clap::ValueEnum;
+
+/// The framerate that visualizations get rendered at.
+pub const FRAMERATE: u32 = 20;
+
+/// The size that visualizations are considered to have before scaling, which
+/// only matters for deriving one target dimension from the other.
+pub const SOURCE_SIZE: (u32, u32) = (640, 360);
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Visualizer {
+    /// Frequency bars, through ffmpeg's `showfreqs`
+    Bars,
+    /// The waveform, through ffmpeg's `showwaves`
+    Waveform,
+}
+
+impl Visualizer {
+    /// The filtergraph that turns the audio stream `track` into the `[v]`
+    /// video stream.
+    pub fn filter(
+        self,
+        track: usize,
+        width: u32,
+        height: u32,
+    ) -> String {
+        let source = match self {
+            Visualizer::Bars => format!(
+                "showfreqs=s={}x{}:mode=bar:fscale=log:ascale=sqrt:\
+                 colors=white,fps={}",
+                width, height, FRAMERATE,
+            ),
+            Visualizer::Waveform => format!(
+                "showwaves=s={}x{}:mode=cline:rate={}:colors=white",
+                width, height, FRAMERATE,
+            ),
+        };
+
+        format!("[0:a:{}]{}[v]", track, source)
+    }
+}
@@ -0,0 +1,93 @@
+//! Transcription of the soundtrack into subtitles with whisper.cpp.
+
+use std::{
+    io,
+    path::Path,
+};
+
+use whisper_rs::{
+    FullParams,
+    SamplingStrategy,
+    WhisperContext,
+    WhisperContextParameters,
+};
+
+use crate::{
+    audio::{
+        read_samples,
+        AudioInput,
+    },
+    subtitles::{
+        Cue,
+        Track,
+    },
+};
+
+/// The sample rate that whisper models take.
+const SAMPLE_RATE: usize = 16000;
+
+fn whisper_error(error: whisper_rs::WhisperError) -> io::Error {
+    io::Error::other(format!("whisper failed: {}", error))
+}
+
+/// Transcribes the soundtrack with the ggml model at `model_path`. Without a
+/// `language`, whisper detects it. The cues are already relative to the start
+/// of the trim.
+pub fn transcribe(
+    input: &AudioInput,
+    model_path: &Path,
+    language: Option<&str>,
+) -> io::Result<Track> {
+    let samples = read_samples(input, SAMPLE_RATE)?;
+
+    let context = WhisperContext::new_with_params(
+        &format!("{}", model_path.display()),
+        WhisperContextParameters::default(),
+    )
+    .map_err(whisper_error)?;
+    let mut state = context.create_state().map_err(whisper_error)?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy {
+        best_of: 1,
+    });
+    params.set_language(Some(language.unwrap_or("auto")));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+
+    state.full(params, &samples).map_err(whisper_error)?;
+
+    let mut cues = vec![];
+    for segment in 0 .. state.full_n_segments().map_err(whisper_error)? {
+        let text = state
+            .full_get_segment_text_lossy(segment)
+            .map_err(whisper_error)?;
+
+        // timestamps are in hundredths of a second
+        let start =
+            state.full_get_segment_t0(segment).map_err(whisper_error)?;
+        let end = state.full_get_segment_t1(segment).map_err(whisper_error)?;
+
+        cues.push(Cue {
+            start_ms: start as u64 * 10,
+            end_ms: end as u64 * 10,
+            lines: vec![text.trim().to_owned()],
+        });
+    }
+
+    let language = match language {
+        Some(language) => language.to_owned(),
+        None => state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .unwrap_or("und")
+            .to_owned(),
+    };
+
+    Ok(Track {
+        language,
+        cues,
+    })
+}
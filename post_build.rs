@@ -83,6 +83,8 @@ fn encode_wasm_js_decl(
         fn finish(mut self) -> String {
             self.contents.push(b'\"');
             self.contents.push(b';');
+            self.contents
+                .extend(b"\nconst read_wasm_b64 = () => wasm_b64;\n\n");
             return String::from_utf8(self.contents).unwrap();
         }
     }
@@ -228,14 +230,19 @@ fn main() {
         eprintln!("Written to stdout. Please copy the output.");
     }
     else {
-        post_to_websocket(&*wasm_b64, 7953, &*crate_name);
+        // the remote for the player goes along with it
+        let files = [
+            (format!("{}.js", crate_name), &*wasm_b64),
+            ("remote.js".to_owned(), include_str!("./remote.js")),
+        ];
+
+        post_to_websocket(&files, 7953);
     }
 }
 
 fn post_to_websocket(
-    contents: &str,
+    files: &[(String, &str)],
     port_number: u16,
-    crate_name: &str,
 ) {
     // start the websocket server
     let server =
@@ -248,19 +255,21 @@ fn post_to_websocket(
     let mut websocket = tungstenite::accept(stream.unwrap()).unwrap();
 
     eprintln!("Listener found. Uploading...");
-    let message = serde_json::json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "pushFile",
-        "params": {
-            "filename": format!("{}.js", crate_name),
-            "content": contents,
-            "server": "home",
-        }
-    })
-    .to_string();
+    for (id, (filename, contents)) in files.iter().enumerate() {
+        let message = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id + 1,
+            "method": "pushFile",
+            "params": {
+                "filename": filename,
+                "content": contents,
+                "server": "home",
+            }
+        })
+        .to_string();
 
-    websocket.write_message(Message::Text(message)).unwrap();
+        websocket.write_message(Message::Text(message)).unwrap();
+    }
 
     websocket.close(None).unwrap();
 }
@@ -0,0 +1,51 @@
+/**
+ * Sends a command to the player, so that it doesn't take writing to its port
+ * by hand, e.g. `run remote.js seek 30` or `run remote.js volume 0.3`.
+ *
+ * `--port <port>` goes to a player started with `--port`, `--instance <name>`
+ * to one started with `--instance`, and `--jukebox` goes to the jukebox
+ * instead, e.g. `run remote.js --jukebox skip`.
+ */
+
+const CONTROL_PORT = 20;
+const JUKEBOX_PORT = 21;
+
+/** The port of the instance standing in for `port`, as the player picks it. */
+function instancePort(name, port) {
+    // 32-bit FNV-1a
+    let hash = 0x811c9dc5;
+    for (const byte of new TextEncoder().encode(name)) {
+        hash = Math.imul(hash ^ byte, 0x01000193) >>> 0;
+    }
+
+    return port + 100 * (1 + hash % 10000);
+}
+
+/** @param {NS} ns */
+export async function main(ns) {
+    let port = CONTROL_PORT;
+    let args = ns.args.slice();
+
+    if (args[0] === "--jukebox") {
+        port = JUKEBOX_PORT;
+        args = args.slice(1);
+    }
+    else if (args[0] === "--port") {
+        port = Number(args[1]);
+        args = args.slice(2);
+    }
+    else if (args[0] === "--instance") {
+        port = instancePort(String(args[1]), CONTROL_PORT);
+        args = args.slice(2);
+    }
+
+    if (!Number.isInteger(port) || port < 1 || args.length === 0) {
+        ns.tprint(
+            "usage: run remote.js [--port <port> | --instance <name> | " +
+                "--jukebox] <command>...",
+        );
+        return;
+    }
+
+    ns.writePort(port, args.join(" "));
+}
@@ -0,0 +1,213 @@
+//! Small looping animations, like spinners, logos, and confetti, for other
+//! scripts to put up in tail windows of their own, out of the same movies
+//! that the player plays. Unlike the player, this keeps every frame in
+//! memory, which is only worth it for a movie of a few dozen of them.
+
+use std::io;
+
+use bitburner_video::{
+    self as movie,
+    Decoded,
+};
+
+use crate::{
+    open_movie,
+    read_chunks,
+    parts::Contents,
+    tail::{
+        self,
+        CharSize,
+    },
+    Killed,
+    NS,
+};
+
+/// Where an animation goes in a tail window larger than it.
+#[derive(Clone, Copy, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// How far across the spare room the animation goes, from the left and
+    /// from the top, as a fraction of it.
+    fn fractions(self) -> (f64, f64) {
+        match self {
+            Anchor::TopLeft => (0., 0.),
+            Anchor::Top => (0.5, 0.),
+            Anchor::TopRight => (1., 0.),
+            Anchor::Left => (0., 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1., 0.5),
+            Anchor::BottomLeft => (0., 1.),
+            Anchor::Bottom => (0.5, 1.),
+            Anchor::BottomRight => (1., 1.),
+        }
+    }
+}
+
+/// The frames of an animation, every one of them decoded.
+pub struct Movie {
+    framerate: f64,
+    width: u32,
+    height: u32,
+    /// Every frame, a line per row, each ending with a newline.
+    frames: Vec<String>,
+}
+
+impl Movie {
+    /// Makes an animation out of frames of its own, at `framerate`, which
+    /// are as wide and as tall as the first of them.
+    pub fn new(
+        framerate: f64,
+        frames: Vec<String>,
+    ) -> Movie {
+        let first = frames.first().map_or("", |f| f.as_str());
+        let width = first.lines().map(movie::visible_width).max().unwrap_or(0);
+
+        Movie {
+            framerate,
+            width: width as u32,
+            height: first.lines().count() as u32,
+            frames,
+        }
+    }
+
+    /// Reads the movie out of `filename`, as the player would. The frames
+    /// that are corrupt show the one before them instead.
+    pub fn load(
+        ns: &NS,
+        filename: &str,
+    ) -> io::Result<Movie> {
+        let contents = Contents::read(ns, filename).ok_or_else(|| {
+            let message = format!("{} is empty or doesn't exist", filename);
+            io::Error::new(io::ErrorKind::NotFound, message)
+        })?;
+
+        let mut reader = open_movie(ns, &contents)?;
+        let header = movie::read_header(&mut reader)?;
+        read_chunks(&contents, &mut reader)?;
+
+        let mut decoder =
+            movie::Frames::new(reader, header.width, header.height);
+        decoder.check_first()?;
+
+        let mut frames: Vec<String> = vec![];
+        loop {
+            let mut frame = String::new();
+
+            match decoder.read_frame(&mut frame)? {
+                Decoded::Whole => frames.push(frame),
+                Decoded::Corrupt => {
+                    let last = frames.last().cloned().unwrap_or_default();
+                    frames.push(last);
+                },
+                Decoded::End => break,
+            }
+        }
+
+        Ok(Movie {
+            framerate: header.framerate,
+            width: header.width,
+            height: header.height,
+            frames,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// How long a single play of it takes, in milliseconds.
+    pub fn duration_ms(&self) -> f64 {
+        self.frames.len() as f64 * 1000. / self.framerate
+    }
+
+    /// The frame that's up `ms` into the animation, as it loops.
+    pub fn frame_at(
+        &self,
+        ms: f64,
+    ) -> &str {
+        if self.frames.is_empty() {
+            return "";
+        }
+
+        let index = (ms.max(0.) * self.framerate / 1000.) as usize;
+        &self.frames[index % self.frames.len()]
+    }
+
+    /// Puts `frame` where `anchor` has it in `columns` by `rows` characters,
+    /// with blanks around it.
+    pub fn place(
+        &self,
+        frame: &str,
+        anchor: Anchor,
+        columns: u32,
+        rows: u32,
+    ) -> String {
+        let (across, down) = anchor.fractions();
+        let spare = |room: u32, size: u32, fraction: f64| {
+            (room.saturating_sub(size) as f64 * fraction).floor() as usize
+        };
+        let left = spare(columns, self.width, across);
+        let top = spare(rows, self.height, down);
+
+        let margin = " ".repeat(left);
+        let mut placed = "\n".repeat(top);
+
+        for line in frame.split_inclusive('\n') {
+            placed += &margin;
+            placed += line;
+        }
+
+        placed
+    }
+}
+
+/// Plays `movie` in the tail window of the script once, where `anchor` has
+/// it.
+pub async fn play_animation(
+    ns: &NS,
+    movie: &Movie,
+    anchor: Anchor,
+) -> Result<(), Killed> {
+    loop_animation(ns, movie, anchor, 1).await
+}
+
+/// Plays `movie` in the tail window of the script `loops` times over, where
+/// `anchor` has it.
+pub async fn loop_animation(
+    ns: &NS,
+    movie: &Movie,
+    anchor: Anchor,
+    loops: usize,
+) -> Result<(), Killed> {
+    // the window is only looked into once, as the animation is short
+    let char_size = CharSize::detect(ns);
+    let (columns, rows) = tail::size(ns)
+        .map_or((movie.width, movie.height), |(w, h)| char_size.fits(w, h));
+    let frame_ms = 1000. / movie.framerate;
+
+    for _ in 0 .. loops {
+        for frame in &movie.frames {
+            ns.clearLog();
+            ns.print(&movie.place(frame, anchor, columns, rows));
+            ns.sleep(frame_ms).await?;
+        }
+    }
+
+    Ok(())
+}
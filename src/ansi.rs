@@ -0,0 +1,311 @@
+//! Reading the colour codes that the frames and the overlays are drawn with,
+//! for whatever has to draw them other than as they are.
+
+/// A colour as a code gives it.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Color {
+    /// Whatever the log is drawn in.
+    #[default]
+    Default,
+    /// One of the 256 colours, the first 16 of which are the named ones.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// The named colours and their bright versions, as Bitburner draws them.
+const NAMED: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 49, 49),
+    (13, 188, 121),
+    (229, 229, 16),
+    (36, 114, 200),
+    (188, 63, 188),
+    (17, 168, 205),
+    (229, 229, 229),
+    (102, 102, 102),
+    (241, 76, 76),
+    (35, 209, 139),
+    (245, 245, 67),
+    (59, 142, 234),
+    (214, 112, 214),
+    (41, 184, 219),
+    (255, 255, 255),
+];
+
+impl Color {
+    /// The red, green, and blue of the colour, unless it's the default one.
+    pub fn rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::Default => None,
+            Color::Indexed(i @ 0 ..= 15) => Some(NAMED[i as usize]),
+            // a 6x6x6 cube, and then 24 greys
+            Color::Indexed(i @ 16 ..= 231) => {
+                let level = |l: u8| if l == 0 { 0 } else { 55 + l * 40 };
+                let i = i - 16;
+                Some((level(i / 36), level(i / 6 % 6), level(i % 6)))
+            },
+            Color::Indexed(i) => {
+                let grey = 8 + (i - 232) * 10;
+                Some((grey, grey, grey))
+            },
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+        }
+    }
+
+    /// The colour as CSS, unless it's the default one.
+    pub fn css(self) -> Option<String> {
+        self.rgb().map(|(r, g, b)| format!("rgb({},{},{})", r, g, b))
+    }
+
+    /// The parameters that set the colour, like `38;5;196`.
+    fn code(
+        self,
+        layer: Layer,
+    ) -> String {
+        let base = match layer {
+            Layer::Foreground => 38,
+            Layer::Background => 48,
+        };
+
+        match self {
+            Color::Default => format!("{}", base + 1),
+            Color::Indexed(i) => format!("{};5;{}", base, i),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base, r, g, b),
+        }
+    }
+}
+
+/// Which of the colours of the text a code sets.
+#[derive(Clone, Copy)]
+pub enum Layer {
+    Foreground,
+    Background,
+}
+
+/// What one of the parameters of a colour code does.
+enum Param {
+    Reset,
+    Bold(bool),
+    Inverse(bool),
+    Color(Layer, Color),
+    /// Whatever else, which is more than what the log draws anyway.
+    Other(u8),
+}
+
+impl Param {
+    fn code(&self) -> String {
+        match self {
+            Param::Reset => "0".into(),
+            Param::Bold(true) => "1".into(),
+            Param::Bold(false) => "22".into(),
+            Param::Inverse(true) => "7".into(),
+            Param::Inverse(false) => "27".into(),
+            Param::Color(layer, color) => color.code(*layer),
+            Param::Other(param) => param.to_string(),
+        }
+    }
+}
+
+/// Reads the parameters of a `\x1b[...m` code, like `0;97;40`.
+fn parse(params: &str) -> Vec<Param> {
+    let mut params =
+        params.split(';').map(|p| p.parse::<u8>().unwrap_or(0));
+    let mut parsed = vec![];
+
+    while let Some(param) = params.next() {
+        use Layer::*;
+
+        parsed.push(match param {
+            0 => Param::Reset,
+            1 => Param::Bold(true),
+            22 => Param::Bold(false),
+            7 => Param::Inverse(true),
+            27 => Param::Inverse(false),
+            30 ..= 37 => Param::Color(Foreground, Color::Indexed(param - 30)),
+            90 ..= 97 => {
+                Param::Color(Foreground, Color::Indexed(param - 90 + 8))
+            },
+            39 => Param::Color(Foreground, Color::Default),
+            40 ..= 47 => Param::Color(Background, Color::Indexed(param - 40)),
+            100 ..= 107 => {
+                Param::Color(Background, Color::Indexed(param - 100 + 8))
+            },
+            49 => Param::Color(Background, Color::Default),
+            38 | 48 => {
+                let layer = match param {
+                    38 => Foreground,
+                    _ => Background,
+                };
+                let color = match params.next() {
+                    Some(5) => params.next().map(Color::Indexed),
+                    Some(2) => {
+                        let mut next = || params.next().unwrap_or(0);
+                        Some(Color::Rgb(next(), next(), next()))
+                    },
+                    _ => None,
+                };
+
+                match color {
+                    Some(color) => Param::Color(layer, color),
+                    None => continue,
+                }
+            },
+            _ => Param::Other(param),
+        });
+    }
+
+    parsed
+}
+
+/// What the text after the codes so far gets drawn like.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    pub foreground: Color,
+    pub background: Color,
+    pub bold: bool,
+    /// Whether the foreground and the background are swapped.
+    pub inverse: bool,
+}
+
+impl Style {
+    /// Applies the parameters of a `\x1b[...m` code, like `0;97;40`.
+    pub fn apply(
+        &mut self,
+        params: &str,
+    ) {
+        for param in parse(params) {
+            match param {
+                Param::Reset => *self = Style::default(),
+                Param::Bold(bold) => self.bold = bold,
+                Param::Inverse(inverse) => self.inverse = inverse,
+                Param::Color(Layer::Foreground, c) => self.foreground = c,
+                Param::Color(Layer::Background, c) => self.background = c,
+                Param::Other(_) => {},
+            }
+        }
+    }
+
+    /// The parameters of a code that sets this style from any other, like
+    /// `0;1;38;5;196`.
+    pub fn code(&self) -> String {
+        let mut params = vec![Param::Reset];
+        if self.bold {
+            params.push(Param::Bold(true));
+        }
+        if self.inverse {
+            params.push(Param::Inverse(true));
+        }
+        params.push(Param::Color(Layer::Foreground, self.foreground));
+        params.push(Param::Color(Layer::Background, self.background));
+
+        params.iter().map(Param::code).collect::<Vec<_>>().join(";")
+    }
+
+    /// The foreground and the background as they get drawn.
+    pub fn colors(&self) -> (Color, Color) {
+        match self.inverse {
+            true => (self.background, self.foreground),
+            false => (self.foreground, self.background),
+        }
+    }
+}
+
+/// What a frame comes apart into.
+pub enum Piece<'a> {
+    Text(&'a str),
+    /// The parameters of a colour code, without the `\x1b[` and the `m`.
+    Color(&'a str),
+    /// Any other escape sequence, as it is.
+    Escape(&'a str),
+}
+
+/// Splits `text` into the runs of text and the escape sequences between them.
+pub fn pieces(text: &str) -> impl Iterator<Item = Piece<'_>> {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        if let Some(sequence) = rest.strip_prefix('\u{1b}') {
+            // a sequence ends with its first letter, like the `m`
+            let end = sequence
+                .find(|c: char| c.is_ascii_alphabetic())
+                .map_or(sequence.len(), |e| e + 1);
+            let (whole, after) = rest.split_at(end + 1);
+            rest = after;
+
+            return Some(match whole.strip_suffix('m') {
+                Some(code) if code.starts_with("\u{1b}[") => {
+                    Piece::Color(&code[2 ..])
+                },
+                _ => Piece::Escape(whole),
+            });
+        }
+
+        let end = rest.find('\u{1b}').unwrap_or(rest.len());
+        let (text, after) = rest.split_at(end);
+        rest = after;
+
+        Some(Piece::Text(text))
+    })
+}
+
+/// Splits `text` into runs of text drawn alike, each with its style, leaving
+/// out any escape sequences that aren't colours.
+pub fn runs(text: &str) -> Vec<(Style, String)> {
+    let mut runs: Vec<(Style, String)> = vec![];
+    let mut style = Style::default();
+
+    for piece in pieces(text) {
+        match piece {
+            Piece::Color(params) => style.apply(params),
+            Piece::Escape(_) => {},
+            // a code that changes nothing doesn't start another run
+            Piece::Text(text) => {
+                match runs.last_mut() {
+                    Some((last, run)) if *last == style => *run += text,
+                    _ => runs.push((style, text.to_owned())),
+                }
+            },
+        }
+    }
+
+    runs
+}
+
+/// Rewrites every colour that the codes of `text` set with `recolor`, given
+/// which of the colours of the text it is, leaving the rest as it is.
+pub fn recolor(
+    text: &str,
+    mut recolor: impl FnMut(Layer, Color) -> Color,
+) -> String {
+    let mut recolored = String::with_capacity(text.len());
+
+    for piece in pieces(text) {
+        match piece {
+            Piece::Text(text) | Piece::Escape(text) => recolored += text,
+            Piece::Color(params) => {
+                let codes = parse(params)
+                    .into_iter()
+                    .map(|param| {
+                        match param {
+                            Param::Color(layer, color) => {
+                                Param::Color(layer, recolor(layer, color))
+                            },
+                            param => param,
+                        }
+                        .code()
+                    })
+                    .collect::<Vec<_>>();
+
+                recolored += "\u{1b}[";
+                recolored += &codes.join(";");
+                recolored += "m";
+            },
+        }
+    }
+
+    recolored
+}
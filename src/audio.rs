@@ -0,0 +1,178 @@
+//! The soundtrack of a movie, which is either the audio of the original or a
+//! chiptune, as long as the `audio` feature is there to play them.
+
+#[cfg(feature = "audio")]
+use base64::engine::Engine as _;
+#[cfg(feature = "audio")]
+use bitburner_video as movie;
+use bitburner_video::Chunk;
+
+#[cfg(feature = "audio")]
+use crate::{
+    chiptune::ChipTune,
+    document::Audio,
+};
+
+/// Something that plays alongside the frames of a movie.
+pub trait Soundtrack {
+    fn play(&mut self);
+
+    fn stop(&mut self);
+
+    fn pause(&mut self);
+
+    /// Resumes a paused soundtrack from `position`, in seconds.
+    fn resume(
+        &mut self,
+        position: f64,
+    );
+
+    /// Moves the soundtrack to `position`, in seconds, whether it's playing,
+    /// paused, or yet to be played.
+    fn seek(
+        &mut self,
+        position: f64,
+    );
+
+    /// How far into the soundtrack the playback is, in seconds.
+    fn position(&self) -> f64;
+
+    /// The length of the soundtrack in seconds, if it's known yet.
+    fn duration(&self) -> Option<f64>;
+
+    /// Sets the volume, clamped to the `0.0 ..= 1.0` range.
+    fn set_volume(
+        &mut self,
+        volume: f64,
+    );
+
+    fn set_muted(
+        &mut self,
+        muted: bool,
+    );
+
+    /// Plays the soundtrack faster, or slower, by the factor.
+    fn set_speed(
+        &mut self,
+        speed: f64,
+    );
+
+    /// What stops the soundtrack without a hold of it, for when the script
+    /// gets killed.
+    fn stopper(&self) -> Box<dyn Fn()>;
+}
+
+/// The soundtrack out of the chunks of a movie, if it has one, the real
+/// audio taking priority over the chiptune.
+#[cfg(feature = "audio")]
+pub fn soundtrack(chunks: &[Chunk]) -> Option<Box<dyn Soundtrack>> {
+    movie::find_chunk(chunks, movie::AUDIO_TAG)
+        .and_then(|c| AudioTrack::from_chunk(&c.payload))
+        .map(|a| Box::new(a) as Box<dyn Soundtrack>)
+        .or_else(|| {
+            movie::find_chunk(chunks, movie::TUNE_TAG)
+                .and_then(|c| ChipTune::from_chunk(&c.payload))
+                .map(|t| Box::new(t) as Box<dyn Soundtrack>)
+        })
+}
+
+/// No soundtrack, as there's nothing to play it with.
+#[cfg(not(feature = "audio"))]
+pub fn soundtrack(_: &[Chunk]) -> Option<Box<dyn Soundtrack>> {
+    None
+}
+
+/// The soundtrack of a movie as the original audio.
+#[cfg(feature = "audio")]
+pub struct AudioTrack {
+    element: Audio,
+}
+
+#[cfg(feature = "audio")]
+impl AudioTrack {
+    /// Creates the track out of the payload of an audio chunk.
+    pub fn from_chunk(payload: &[u8]) -> Option<AudioTrack> {
+        let split = payload.iter().position(|b| *b == b'\n')?;
+        let mime = std::str::from_utf8(&payload[.. split]).ok()?;
+        let data = &payload[split + 1 ..];
+
+        // the base64 goes straight into the URL, which is sized up front, as
+        // a soundtrack can run to megabytes
+        let mut url = format!("data:{};base64,", mime);
+        url.reserve(base64::encoded_len(data.len(), true)?);
+        base64::prelude::BASE64_STANDARD.encode_string(data, &mut url);
+
+        Some(AudioTrack {
+            element: Audio::new(&url),
+        })
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Soundtrack for AudioTrack {
+    fn play(&mut self) {
+        // autoplay can get rejected, but that's no reason to stop the video
+        let promise = self.element.play();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        });
+    }
+
+    fn stop(&mut self) {
+        self.element.pause();
+    }
+
+    fn pause(&mut self) {
+        self.element.pause();
+    }
+
+    fn resume(
+        &mut self,
+        position: f64,
+    ) {
+        self.element.set_current_time(position);
+        self.play();
+    }
+
+    fn seek(
+        &mut self,
+        position: f64,
+    ) {
+        self.element.set_current_time(position);
+    }
+
+    fn position(&self) -> f64 {
+        self.element.current_time()
+    }
+
+    fn duration(&self) -> Option<f64> {
+        // NaN until the metadata is loaded
+        Some(self.element.duration()).filter(|d| d.is_finite())
+    }
+
+    fn set_volume(
+        &mut self,
+        volume: f64,
+    ) {
+        self.element.set_volume(volume.clamp(0., 1.));
+    }
+
+    fn set_muted(
+        &mut self,
+        muted: bool,
+    ) {
+        self.element.set_muted(muted);
+    }
+
+    fn set_speed(
+        &mut self,
+        speed: f64,
+    ) {
+        self.element.set_playback_rate(speed);
+    }
+
+    fn stopper(&self) -> Box<dyn Fn()> {
+        let element = self.element.clone();
+        Box::new(move || element.pause())
+    }
+}
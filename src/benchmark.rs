@@ -0,0 +1,96 @@
+//! Timing each step of putting a frame up, for `--benchmark`, which plays the
+//! movie as fast as it goes to tell where the time of a frame goes.
+
+/// The upper bounds of the buckets that the times go in, in milliseconds,
+/// with one more bucket past the last for the rest.
+const BUCKETS: [f64; 7] = [0.5, 1., 2., 4., 8., 16., 32.];
+
+/// How wide the bar of the fullest bucket gets.
+const BAR_WIDTH: usize = 40;
+
+pub enum Step {
+    /// Decompressing the frame.
+    Decode,
+    /// Drawing the overlays and the status line over it.
+    Format,
+    /// Handing it to the tail window.
+    Print,
+}
+
+#[derive(Default)]
+struct Histogram {
+    counts: [usize; BUCKETS.len() + 1],
+    total_ms: f64,
+    max_ms: f64,
+}
+
+impl Histogram {
+    fn record(
+        &mut self,
+        ms: f64,
+    ) {
+        let bucket = BUCKETS.iter().position(|b| ms < *b);
+        self.counts[bucket.unwrap_or(BUCKETS.len())] += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    fn report(
+        &self,
+        name: &str,
+    ) -> String {
+        let total = self.counts.iter().sum::<usize>().max(1);
+        let fullest = self.counts.iter().copied().max().unwrap_or(0).max(1);
+
+        let mut report = format!(
+            "{}: {:.2} ms on average, {:.2} ms at most\n",
+            name,
+            self.total_ms / total as f64,
+            self.max_ms,
+        );
+
+        for (index, count) in self.counts.iter().enumerate() {
+            let bound = match BUCKETS.get(index) {
+                Some(bound) => format!("< {}", bound),
+                None => format!("{}+", BUCKETS[BUCKETS.len() - 1]),
+            };
+            let bar = "#".repeat(count * BAR_WIDTH / fullest);
+
+            report += &format!("  {:>6} ms {:>6} {}\n", bound, count, bar);
+        }
+
+        report
+    }
+}
+
+#[derive(Default)]
+pub struct Benchmark {
+    decode: Histogram,
+    format: Histogram,
+    print: Histogram,
+}
+
+impl Benchmark {
+    /// Counts `ms` towards the time that the step took for a frame.
+    pub fn record(
+        &mut self,
+        step: Step,
+        ms: f64,
+    ) {
+        match step {
+            Step::Decode => self.decode.record(ms),
+            Step::Format => self.format.record(ms),
+            Step::Print => self.print.record(ms),
+        }
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = self.decode.report("decoding");
+        report += &self.format.report("formatting");
+        report += &self.print.report("printing");
+
+        // the terminal adds the newline of its own
+        report.pop();
+        report
+    }
+}
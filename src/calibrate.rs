@@ -0,0 +1,283 @@
+//! Working out how large a character of the tail window is, for
+//! `--calibrate`, by putting up a grid and having it fitted by hand, and
+//! then measuring the window.
+//!
+//! If the size of the window can't be had, the grid goes up in windows of
+//! different sizes instead, asking which of them it fits, narrowing it down
+//! a half at a time.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    logger,
+    ns::{
+        args,
+        ui,
+    },
+    panic,
+    script,
+    storage,
+    tail::{
+        self,
+        CharSize,
+        CALIBRATION_FILE,
+    },
+    Killed,
+    NS,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(method, catch)]
+    async fn prompt(
+        ns: &NS,
+        text: &str,
+        options: &JsValue,
+    ) -> Result<JsValue, JsValue>;
+}
+
+/// The size of the grid, in characters.
+const GRID_COLUMNS: u32 = 40;
+const GRID_ROWS: u32 = 10;
+
+/// How close the size has to get, in pixels, before it counts as found.
+const PRECISION: f64 = 0.1;
+
+/// The most questions asked about each of the dimensions.
+const MAX_STEPS: usize = 12;
+
+enum Answer {
+    TooSmall,
+    Fits,
+    TooLarge,
+}
+
+/// Asks `question`, with the choices for a window that's too small, just
+/// right, and too large, in that order. Dismissing the prompt gives nothing.
+async fn ask(
+    ns: &NS,
+    question: &str,
+    choices: [&str; 3],
+) -> Result<Option<Answer>, Killed> {
+    let options = js_sys::Object::new();
+    let choices_js = choices.iter().map(|c| JsValue::from_str(c));
+    let set = |key: &str, value: &JsValue| {
+        js_sys::Reflect::set(&options, &JsValue::from_str(key), value)
+    };
+    let _ = set("type", &JsValue::from_str("select"));
+    let _ = set("choices", &choices_js.collect::<js_sys::Array>());
+
+    let answer = ns.prompt(question, &options).await.map_err(|_| Killed)?;
+
+    Ok(match answer.as_string() {
+        Some(a) if a == choices[0] => Some(Answer::TooSmall),
+        Some(a) if a == choices[1] => Some(Answer::Fits),
+        Some(a) if a == choices[2] => Some(Answer::TooLarge),
+        _ => None,
+    })
+}
+
+/// A row of the grid, as digits counting the columns.
+fn digits() -> String {
+    (0 .. GRID_COLUMNS)
+        .map(|c| char::from(b'0' + (c % 10) as u8))
+        .collect()
+}
+
+/// Asks `question`, to be answered yes or no. Dismissing the prompt counts as
+/// a no.
+async fn confirm(
+    ns: &NS,
+    question: &str,
+) -> Result<bool, Killed> {
+    let options = js_sys::Object::new();
+    let answer = ns.prompt(question, &options).await.map_err(|_| Killed)?;
+
+    Ok(answer.as_bool() == Some(true))
+}
+
+/// Has the tail window resized by hand to just fit the grid, and divides its
+/// size up between the characters of the grid, unless it's said not to fit
+/// or the size of the window can't be had.
+async fn measure(
+    ns: &NS,
+    guess: CharSize,
+) -> Result<Option<CharSize>, Killed> {
+    let digits = digits();
+    let grid = (1 ..= GRID_ROWS)
+        .map(|r| {
+            let r = r.to_string();
+            format!("{}{}", r, &digits[r.len() ..])
+        })
+        .collect::<Vec<_>>();
+
+    let (width, height) = guess.tail_size(GRID_COLUMNS, GRID_ROWS);
+    ns.clearLog();
+    ns.print(&grid.join("\n"));
+    ui::resize_tail(ns, width, height);
+
+    let question = format!(
+        "Resize the tail window until the rows numbered 1 to {} just fit, each \
+         on a line of its own, with no room to spare. Does it fit?",
+        GRID_ROWS,
+    );
+    if !confirm(ns, &question).await? {
+        return Ok(None);
+    }
+
+    let Some((width, height)) = tail::size(ns)
+    else {
+        return Ok(None);
+    };
+
+    let size = CharSize {
+        width: width / GRID_COLUMNS as f64,
+        height: height / GRID_ROWS as f64,
+    };
+    Ok((0. < size.width && 0. < size.height).then_some(size))
+}
+
+/// Narrows down a size between `low` and `high`, putting it up with `show`
+/// before each question, unless the calibration gets called off.
+async fn search(
+    ns: &NS,
+    mut low: f64,
+    mut high: f64,
+    question: &str,
+    choices: [&str; 3],
+    show: impl Fn(f64),
+) -> Result<Option<f64>, Killed> {
+    for _ in 0 .. MAX_STEPS {
+        let size = (low + high) / 2.;
+        show(size);
+
+        match ask(ns, question, choices).await? {
+            Some(Answer::TooSmall) => low = size,
+            Some(Answer::Fits) => return Ok(Some(size)),
+            Some(Answer::TooLarge) => high = size,
+            None => return Ok(None),
+        }
+
+        if high - low < PRECISION {
+            break;
+        }
+    }
+
+    Ok(Some((low + high) / 2.))
+}
+
+/// Calibrates, for `run bitoxide.js --calibrate`, which is the same as the
+/// player given `--calibrate` and nothing to play.
+#[wasm_bindgen]
+pub async fn calibrate_rs(ns: &NS) {
+    script::run(ns, async {
+        panic::install();
+        logger::install(logger::Sink::Terminal);
+        ns.disableLog("ALL");
+
+        let no_tail = args::args(ns).any(|a| a.text() == "--no-tail");
+        if !no_tail {
+            ui::open_tail(ns);
+        }
+
+        let _ = calibrate(ns).await;
+    })
+    .await;
+}
+
+/// Works out the size of a character, and writes it to the calibration file
+/// for the player and the encoder to go by.
+pub async fn calibrate(ns: &NS) -> Result<(), Killed> {
+    let guess = CharSize::detect(ns);
+    let size = match measure(ns, guess).await? {
+        Some(size) => size,
+        None => {
+            let Some(size) = narrow_down(ns, guess).await?
+            else {
+                log::info!("called off the calibration");
+                return Ok(());
+            };
+
+            size
+        },
+    };
+
+    let CharSize { width, height } = size;
+    let saved = format!("{:.2} {:.2}", width, height);
+    storage::write_text(ns, CALIBRATION_FILE, &saved);
+    log::info!(
+        "a character is {:.2} by {:.2} pixels, as saved to {}",
+        width,
+        height,
+        CALIBRATION_FILE,
+    );
+
+    Ok(())
+}
+
+/// Works out the size of a character by asking about the grid in windows of
+/// different sizes, unless the calibration gets called off.
+async fn narrow_down(
+    ns: &NS,
+    guess: CharSize,
+) -> Result<Option<CharSize>, Killed> {
+    let window = ui::window_size(ns);
+
+    // the tail window can't get any larger than the game
+    let bound = |guess: f64, window: Option<f64>, count: u32| {
+        let high = 2. * guess;
+        window.map_or(high, |w| high.min(w / count as f64))
+    };
+    let high_width = bound(guess.width, window.map(|w| w.0), GRID_COLUMNS);
+    let high_height = bound(guess.height, window.map(|w| w.1), GRID_ROWS);
+
+    let digits = digits();
+    let grid = (1 ..= GRID_ROWS)
+        .map(|r| format!("{:<width$}", r, width = GRID_COLUMNS as usize))
+        .collect::<Vec<_>>();
+
+    let show = |size: CharSize, rows: u32, lines: &[String]| {
+        let (width, height) = size.tail_size(GRID_COLUMNS, rows);
+
+        ns.clearLog();
+        ns.print(&lines.join("\n"));
+        ui::resize_tail(ns, width, height);
+    };
+
+    let lines = vec![digits; GRID_ROWS as usize];
+    let Some(width) = search(
+        ns,
+        guess.width / 2.,
+        high_width,
+        "Does each row of digits fit on a line of its own, with no room to \
+         spare?",
+        ["it wraps", "it fits", "there's room to spare"],
+        |width| {
+            // the rows are left room to wrap into
+            let size = CharSize { width, ..guess };
+            show(size, 2 * GRID_ROWS, &lines);
+        },
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let Some(height) = search(
+        ns,
+        guess.height / 2.,
+        high_height,
+        &format!(
+            "Do the rows numbered 1 to {} fit, with no room to spare?",
+            GRID_ROWS,
+        ),
+        ["some are cut off", "they fit", "there's room to spare"],
+        |height| show(CharSize { width, height }, GRID_ROWS, &grid),
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(CharSize { width, height }))
+}
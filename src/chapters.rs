@@ -0,0 +1,137 @@
+//! The chapter strip: the thumbnails of the chapters around the current one,
+//! drawn over the top rows of the frames.
+
+pub struct Chapter {
+    pub start_ms: f64,
+    pub title: String,
+    /// The rows of the thumbnail, as colored characters.
+    thumbnail: Vec<String>,
+}
+
+pub struct Chapters {
+    /// The size of every thumbnail, in characters.
+    width: usize,
+    height: usize,
+    /// Sorted by start time.
+    pub chapters: Vec<Chapter>,
+}
+
+impl Chapters {
+    /// Reads the chapters out of the payload of a chapter chunk.
+    pub fn from_chunk(payload: &[u8]) -> Option<Chapters> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let mut lines = payload.lines();
+
+        let (width, height) = lines.next()?.split_once(' ')?;
+        let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+        let mut chapters = vec![];
+
+        while let Some(line) = lines.next() {
+            let (start, title) = line.split_once(' ')?;
+
+            chapters.push(Chapter {
+                start_ms: start.parse().ok()?,
+                title: title.to_owned(),
+                thumbnail: lines
+                    .by_ref()
+                    .take(height)
+                    .map(|l| l.to_owned())
+                    .collect(),
+            });
+        }
+
+        chapters.sort_by(|a, b| a.start_ms.total_cmp(&b.start_ms));
+
+        Some(Chapters {
+            width,
+            height,
+            chapters,
+        })
+    }
+
+    /// The index of the chapter that `time_ms` into the movie falls in.
+    pub fn current(
+        &self,
+        time_ms: f64,
+    ) -> Option<usize> {
+        self.chapters
+            .partition_point(|c| c.start_ms <= time_ms)
+            .checked_sub(1)
+    }
+
+    /// Replaces the top rows of the frame with as many thumbnails as fit its
+    /// width, each with its title below it. The strip follows the current
+    /// chapter, whose title is highlighted.
+    pub fn overlay(
+        &self,
+        frame: &str,
+        width: u32,
+        time_ms: f64,
+    ) -> String {
+        const RESET: &str = "\u{1b}[0m";
+
+        let width = width as usize;
+        let fitting = ((width + 1) / (self.width + 1)).max(1);
+        let current = self.current(time_ms).unwrap_or(0);
+
+        // keep the current chapter near the middle of the strip
+        let first = current
+            .saturating_sub(fitting / 2)
+            .min(self.chapters.len().saturating_sub(fitting));
+        let shown =
+            &self.chapters[first .. (first + fitting).min(self.chapters.len())];
+
+        let mut strip = vec![String::new(); self.height + 1];
+
+        for (index, chapter) in (first ..).zip(shown) {
+            let separator = match index == first {
+                true => "",
+                false => " ",
+            };
+
+            for (row, line) in strip.iter_mut().zip(chapter.thumbnail.iter()) {
+                *row += RESET;
+                *row += separator;
+                *row += line;
+            }
+
+            let title =
+                chapter.title.chars().take(self.width).collect::<String>();
+            let style = match index == current {
+                true => "\u{1b}[0;7m",
+                false => "\u{1b}[0;97;40m",
+            };
+
+            let titles = strip.last_mut().unwrap();
+            *titles += RESET;
+            *titles += separator;
+            *titles += style;
+            *titles += &format!("{:<1$}", title, self.width);
+        }
+
+        // blank out the rest of the rows, which would otherwise show a sliver
+        // of the frame
+        let used = (shown.len() * (self.width + 1)).saturating_sub(1);
+        for row in strip.iter_mut() {
+            *row += "\u{1b}[0;40m";
+            row.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+        }
+
+        let rows = frame.lines().collect::<Vec<_>>();
+        let mut output = String::with_capacity(frame.len());
+
+        for (index, row) in rows.iter().enumerate() {
+            match strip.get(index) {
+                Some(strip_row) => {
+                    output += strip_row;
+                    output += RESET;
+                },
+                None => output += row,
+            }
+
+            output += "\n";
+        }
+
+        output
+    }
+}
@@ -0,0 +1,300 @@
+//! Synthesis of the tunes that the encoder reduces soundtracks into, through
+//! the Web Audio API.
+
+use wasm_bindgen::prelude::*;
+
+use crate::audio::Soundtrack;
+
+#[wasm_bindgen]
+extern "C" {
+    #[derive(Clone)]
+    type AudioContext;
+
+    #[wasm_bindgen(constructor)]
+    fn new() -> AudioContext;
+
+    #[wasm_bindgen(method, getter)]
+    fn currentTime(this: &AudioContext) -> f64;
+
+    #[wasm_bindgen(method, getter)]
+    fn destination(this: &AudioContext) -> JsValue;
+
+    #[wasm_bindgen(method)]
+    fn createOscillator(this: &AudioContext) -> OscillatorNode;
+
+    #[wasm_bindgen(method)]
+    fn createGain(this: &AudioContext) -> GainNode;
+
+    #[wasm_bindgen(method)]
+    fn suspend(this: &AudioContext) -> js_sys::Promise;
+
+    #[wasm_bindgen(method, js_name = resume)]
+    fn resume_context(this: &AudioContext) -> js_sys::Promise;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &AudioContext) -> js_sys::Promise;
+
+    type OscillatorNode;
+
+    #[wasm_bindgen(method, setter = type)]
+    fn set_type(
+        this: &OscillatorNode,
+        kind: &str,
+    );
+
+    #[wasm_bindgen(method, getter)]
+    fn frequency(this: &OscillatorNode) -> AudioParam;
+
+    #[wasm_bindgen(method)]
+    fn connect(
+        this: &OscillatorNode,
+        destination: &JsValue,
+    ) -> JsValue;
+
+    #[wasm_bindgen(method)]
+    fn start(
+        this: &OscillatorNode,
+        when: f64,
+    );
+
+    #[wasm_bindgen(method)]
+    fn stop(
+        this: &OscillatorNode,
+        when: f64,
+    );
+
+    type GainNode;
+
+    #[wasm_bindgen(method, getter)]
+    fn gain(this: &GainNode) -> AudioParam;
+
+    #[wasm_bindgen(method, js_name = connect)]
+    fn connect_gain(
+        this: &GainNode,
+        destination: &JsValue,
+    ) -> JsValue;
+
+    type AudioParam;
+
+    #[wasm_bindgen(method, setter)]
+    fn set_value(
+        this: &AudioParam,
+        value: f64,
+    );
+
+    #[wasm_bindgen(method)]
+    fn setValueAtTime(
+        this: &AudioParam,
+        value: f64,
+        start_time: f64,
+    ) -> AudioParam;
+}
+
+/// Square waves are loud. This keys the tune down to about the level of a
+/// regular soundtrack at full volume.
+const LOUDNESS: f64 = 0.15;
+
+/// A tune played on a square wave oscillator.
+pub struct ChipTune {
+    context: AudioContext,
+    master: GainNode,
+    step_secs: f64,
+    runs: Vec<(u8, u8)>,
+    /// The context time at which the tune started playing, or would have if
+    /// it had been played from the beginning at the current speed.
+    start: Option<f64>,
+    /// Where the tune plays from once it gets played, in seconds.
+    offset: f64,
+    /// The oscillator of the notes that are scheduled, if any.
+    oscillator: Option<OscillatorNode>,
+    /// How much faster than its steps the tune plays.
+    speed: f64,
+    volume: f64,
+    muted: bool,
+}
+
+fn note_frequency(note: u8) -> f64 {
+    440. * 2f64.powf((note as f64 - 69.) / 12.)
+}
+
+impl ChipTune {
+    /// Creates the tune out of the payload of a tune chunk.
+    pub fn from_chunk(payload: &[u8]) -> Option<ChipTune> {
+        let split = payload.iter().position(|b| *b == b'\n')?;
+        let step_ms = std::str::from_utf8(&payload[.. split])
+            .ok()?
+            .parse::<f64>()
+            .ok()?;
+        let runs = payload[split + 1 ..]
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+
+        let context = AudioContext::new();
+        let master = context.createGain();
+        master.connect_gain(&context.destination());
+
+        let tune = ChipTune {
+            context,
+            master,
+            step_secs: step_ms / 1000.,
+            runs,
+            start: None,
+            offset: 0.,
+            oscillator: None,
+            speed: 1.,
+            volume: 1.,
+            muted: false,
+        };
+        tune.update_gain();
+
+        Some(tune)
+    }
+
+    fn update_gain(&self) {
+        let gain = if self.muted { 0. } else { self.volume * LOUDNESS };
+        self.master.gain().set_value(gain);
+    }
+
+    /// Schedules the notes from `from` seconds into the tune onwards,
+    /// dropping the ones that were scheduled before.
+    fn schedule(
+        &mut self,
+        from: f64,
+    ) {
+        if let Some(oscillator) = self.oscillator.take() {
+            oscillator.stop(0.);
+        }
+
+        let oscillator = self.context.createOscillator();
+        oscillator.set_type("square");
+
+        // the envelope switches between notes and rests, while the master gain
+        // stays free for the volume controls
+        let envelope = self.context.createGain();
+        oscillator.connect(&envelope);
+        envelope.connect_gain(&self.master);
+
+        let now = self.context.currentTime();
+        let step_secs = self.step_secs / self.speed;
+        let start = now - from / self.speed;
+        let mut time = start;
+
+        // silence, in case the tune ends before `from`
+        envelope.gain().setValueAtTime(0., now);
+
+        for (note, steps) in self.runs.iter() {
+            let end = time + *steps as f64 * step_secs;
+
+            // a note cut into by `from` plays the rest of its length
+            if now < end {
+                let at = time.max(now);
+
+                match note {
+                    0 => {
+                        envelope.gain().setValueAtTime(0., at);
+                    },
+                    note => {
+                        oscillator
+                            .frequency()
+                            .setValueAtTime(note_frequency(*note), at);
+                        envelope.gain().setValueAtTime(1., at);
+                    },
+                }
+            }
+
+            time = end;
+        }
+
+        oscillator.start(now);
+        oscillator.stop(time.max(now));
+        self.oscillator = Some(oscillator);
+        self.start = Some(start);
+    }
+}
+
+impl Soundtrack for ChipTune {
+    /// Schedules the whole tune at once, leaving the timing to the browser.
+    fn play(&mut self) {
+        self.schedule(self.offset);
+    }
+
+    fn stop(&mut self) {
+        let _ = self.context.close();
+    }
+
+    /// Suspends the context, which freezes its clock along with everything
+    /// scheduled on it.
+    fn pause(&mut self) {
+        let _ = self.context.suspend();
+    }
+
+    /// Resumes the context. The whole tune is already scheduled, so it can
+    /// only carry on from where it got suspended, and `position` is ignored.
+    fn resume(
+        &mut self,
+        _position: f64,
+    ) {
+        let _ = self.context.resume_context();
+    }
+
+    /// Reschedules the tune from `position`. A suspended context keeps its
+    /// clock frozen, so this works the same while paused.
+    fn seek(
+        &mut self,
+        position: f64,
+    ) {
+        match self.start {
+            Some(_) => self.schedule(position),
+            None => self.offset = position,
+        }
+    }
+
+    fn position(&self) -> f64 {
+        match self.start {
+            Some(start) => (self.context.currentTime() - start) * self.speed,
+            None => 0.,
+        }
+    }
+
+    fn duration(&self) -> Option<f64> {
+        let steps = self.runs.iter().map(|(_, s)| *s as f64).sum::<f64>();
+        Some(steps * self.step_secs)
+    }
+
+    fn set_volume(
+        &mut self,
+        volume: f64,
+    ) {
+        self.volume = volume.clamp(0., 1.);
+        self.update_gain();
+    }
+
+    fn set_muted(
+        &mut self,
+        muted: bool,
+    ) {
+        self.muted = muted;
+        self.update_gain();
+    }
+
+    /// Reschedules the rest of the tune at the new speed.
+    fn set_speed(
+        &mut self,
+        speed: f64,
+    ) {
+        let position = self.position();
+        self.speed = speed;
+
+        if self.start.is_some() {
+            self.schedule(position);
+        }
+    }
+
+    fn stopper(&self) -> Box<dyn Fn()> {
+        let context = self.context.clone();
+        Box::new(move || {
+            let _ = context.close();
+        })
+    }
+}
@@ -0,0 +1,256 @@
+//! Where the player gets the time from, and the timeline that the frames are
+//! due on by it, which goes by whatever clock it's handed, so that the timing
+//! can be worked through with a clock that only moves when it's told to.
+
+use std::cell::Cell;
+
+use crate::performance_now;
+
+/// Something that tells the time, in milliseconds.
+pub trait Clock {
+    fn now(&self) -> f64;
+}
+
+/// `Date.now()`, since the epoch, for the instants that other scripts go by
+/// as well, like that of `--sync-at`.
+#[derive(Clone, Copy, Default)]
+pub struct WallClock;
+
+impl Clock for WallClock {
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+}
+
+/// `performance.now()`, since the page loaded, for timing the frames.
+#[derive(Clone, Copy, Default)]
+pub struct MonotonicClock;
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> f64 {
+        performance_now()
+    }
+}
+
+/// A clock that stays where it is until it's set or moved on.
+#[derive(Default)]
+pub struct MockClock {
+    now: Cell<f64>,
+}
+
+impl MockClock {
+    pub fn new(now: f64) -> MockClock {
+        MockClock {
+            now: Cell::new(now),
+        }
+    }
+
+    pub fn set(
+        &self,
+        now: f64,
+    ) {
+        self.now.set(now);
+    }
+
+    pub fn advance(
+        &self,
+        ms: f64,
+    ) {
+        self.now.set(self.now.get() + ms);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> f64 {
+        self.now.get()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> f64 {
+        (**self).now()
+    }
+}
+
+/// When each of the frames is due, as counted from when the first one was,
+/// once the playback has started.
+pub struct Schedule<C> {
+    clock: C,
+    first_due: Option<f64>,
+    frame_ms: f64,
+}
+
+impl<C: Clock> Schedule<C> {
+    pub fn new(
+        clock: C,
+        frame_ms: f64,
+    ) -> Schedule<C> {
+        Schedule {
+            clock,
+            first_due: None,
+            frame_ms,
+        }
+    }
+
+    pub fn now(&self) -> f64 {
+        self.clock.now()
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.first_due.is_some()
+    }
+
+    /// Starts the timeline with `frame` due right now.
+    pub fn start(
+        &mut self,
+        frame: usize,
+    ) {
+        self.first_due = Some(self.now() - frame as f64 * self.frame_ms);
+    }
+
+    /// Carries the timeline on from `frame`, as after a seek, unless it
+    /// hasn't started.
+    pub fn restart(
+        &mut self,
+        frame: usize,
+    ) {
+        if self.is_started() {
+            self.start(frame);
+        }
+    }
+
+    /// Puts every frame off by `ms`, as for a pause.
+    pub fn delay(
+        &mut self,
+        ms: f64,
+    ) {
+        if let Some(first_due) = self.first_due.as_mut() {
+            *first_due += ms;
+        }
+    }
+
+    /// Changes how long a frame stays up, keeping `frame` due when it was.
+    pub fn set_frame_ms(
+        &mut self,
+        frame: usize,
+        frame_ms: f64,
+    ) {
+        if let Some(first_due) = self.first_due.as_mut() {
+            *first_due += frame as f64 * (self.frame_ms - frame_ms);
+        }
+
+        self.frame_ms = frame_ms;
+    }
+
+    /// When `frame` is due, once the timeline has started.
+    pub fn due(
+        &self,
+        frame: usize,
+    ) -> Option<f64> {
+        Some(self.first_due? + frame as f64 * self.frame_ms)
+    }
+
+    /// How many of the frames from `frame` on are already late, which is
+    /// none before the timeline has started.
+    pub fn late(
+        &self,
+        frame: usize,
+    ) -> usize {
+        let Some(first_due) = self.first_due
+        else {
+            return 0;
+        };
+
+        let due = (self.now() - first_due) / self.frame_ms;
+        (due.floor() as usize).saturating_sub(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_due_before_the_start() {
+        let clock = MockClock::new(1000.);
+        let mut schedule = Schedule::new(&clock, 100.);
+
+        assert!(!schedule.is_started());
+        assert_eq!(schedule.due(0), None);
+        assert_eq!(schedule.late(0), 0);
+
+        schedule.delay(500.);
+        schedule.restart(3);
+        assert!(!schedule.is_started());
+    }
+
+    #[test]
+    fn frames_are_due_from_the_start() {
+        let clock = MockClock::new(1000.);
+        let mut schedule = Schedule::new(&clock, 100.);
+
+        schedule.start(0);
+        assert_eq!(schedule.due(0), Some(1000.));
+        assert_eq!(schedule.due(5), Some(1500.));
+
+        // starting at a later frame has it due right now
+        schedule.start(10);
+        assert_eq!(schedule.due(10), Some(1000.));
+        assert_eq!(schedule.due(0), Some(0.));
+    }
+
+    #[test]
+    fn frames_run_late_as_the_clock_moves_on() {
+        let clock = MockClock::new(0.);
+        let mut schedule = Schedule::new(&clock, 100.);
+        schedule.start(0);
+
+        assert_eq!(schedule.late(0), 0);
+        clock.advance(250.);
+        assert_eq!(schedule.late(0), 2);
+        assert_eq!(schedule.late(1), 1);
+        assert_eq!(schedule.late(2), 0);
+        assert_eq!(schedule.late(5), 0);
+    }
+
+    #[test]
+    fn a_delay_puts_every_frame_off() {
+        let clock = MockClock::new(0.);
+        let mut schedule = Schedule::new(&clock, 100.);
+        schedule.start(0);
+
+        clock.set(300.);
+        schedule.delay(300.);
+        assert_eq!(schedule.due(3), Some(600.));
+        assert_eq!(schedule.late(0), 0);
+    }
+
+    #[test]
+    fn a_restart_carries_on_from_the_frame() {
+        let clock = MockClock::new(0.);
+        let mut schedule = Schedule::new(&clock, 100.);
+        schedule.start(0);
+
+        clock.set(1000.);
+        schedule.restart(2);
+        assert_eq!(schedule.due(2), Some(1000.));
+        assert_eq!(schedule.due(3), Some(1100.));
+    }
+
+    #[test]
+    fn a_new_frame_length_keeps_the_frame_where_it_was() {
+        let clock = MockClock::new(0.);
+        let mut schedule = Schedule::new(&clock, 100.);
+        schedule.start(0);
+
+        schedule.set_frame_ms(4, 50.);
+        assert_eq!(schedule.due(4), Some(400.));
+        assert_eq!(schedule.due(6), Some(500.));
+
+        // and before the start, there's only the length to change
+        let mut schedule = Schedule::new(&clock, 100.);
+        schedule.set_frame_ms(4, 50.);
+        schedule.start(0);
+        assert_eq!(schedule.due(2), Some(100.));
+    }
+}
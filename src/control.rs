@@ -0,0 +1,209 @@
+//! Runtime control of the player through a Bitburner port.
+//!
+//! Other scripts control playback by writing commands to the port, e.g.
+//! `ns.writePort(20, "volume 0.3")`. Players started with `--port` listen on
+//! that port instead, so that several of them can be told apart.
+
+use crate::{
+    ns::ports::Port,
+    NS,
+};
+
+/// The port that the player listens on for commands, unless given another.
+pub const CONTROL_PORT: u32 = 20;
+
+/// Where a seek goes to.
+#[derive(Clone, Copy)]
+pub enum Seek {
+    /// To this many seconds into the movie.
+    To(f64),
+    /// This many seconds forwards, or backwards if negative.
+    By(f64),
+}
+
+impl Seek {
+    /// Parses `30` as seeking to 30 seconds in, and `+10` and `-10` as
+    /// seeking relative to the current position.
+    pub fn parse(seek: &str) -> Option<Seek> {
+        let seconds = seek.parse::<f64>().ok().filter(|s| s.is_finite())?;
+
+        match seek.starts_with(['+', '-']) {
+            true => Some(Seek::By(seconds)),
+            false => Some(Seek::To(seconds)),
+        }
+    }
+
+    /// The seek that does this one and then `next`.
+    pub fn then(
+        self,
+        next: Seek,
+    ) -> Seek {
+        match (self, next) {
+            (Seek::To(at), Seek::By(by)) => Seek::To(at + by),
+            (Seek::By(by), Seek::By(more)) => Seek::By(by + more),
+            (_, Seek::To(at)) => Seek::To(at),
+        }
+    }
+
+    /// Where the seek lands when starting from `position`, both in seconds.
+    pub fn target(
+        self,
+        position: f64,
+    ) -> f64 {
+        match self {
+            Seek::To(at) => at,
+            Seek::By(by) => position + by,
+        }
+        .max(0.)
+    }
+}
+
+/// The slowest and fastest that the playback goes, which is about what
+/// browsers can do with audio.
+pub const SPEEDS: std::ops::RangeInclusive<f64> = 0.25 ..= 4.;
+
+/// Parses a speed factor, which has to be within [`SPEEDS`].
+pub fn parse_speed(speed: &str) -> Option<f64> {
+    speed.parse().ok().filter(|s| SPEEDS.contains(s))
+}
+
+/// Parses a volume, clamped to the `0.0 ..= 1.0` range, which the likes of
+/// `NaN` have no place in.
+pub fn parse_volume(volume: &str) -> Option<f64> {
+    clamp_volume(volume.parse().ok()?)
+}
+
+/// The volume clamped to the `0.0 ..= 1.0` range, unless it isn't finite.
+pub fn clamp_volume(volume: f64) -> Option<f64> {
+    volume.is_finite().then(|| volume.clamp(0., 1.))
+}
+
+pub enum Command {
+    Pause,
+    Resume,
+    Volume(f64),
+    Mute,
+    Unmute,
+    /// Switches the subtitles to the language, or hides them.
+    Language(Option<String>),
+    Seek(Seek),
+    /// Pauses, then moves this many frames forwards, or backwards if
+    /// negative.
+    Step(i64),
+    /// Plays faster, or slower, by the factor.
+    Speed(f64),
+    /// Puts down the next mark of the A-B repeat, or clears the repeat once
+    /// both are down.
+    MarkRepeat,
+    /// Repeats between the two positions, in seconds, or stops repeating.
+    Repeat(Option<(f64, f64)>),
+    /// Shows or hides the subtitles, keeping to the language.
+    Subtitles(bool),
+    /// Shows or hides the status line.
+    Status(bool),
+    /// Shows the chapter strip for a few seconds.
+    Chapters,
+    /// Shows the credits of the movie for a few seconds.
+    Info,
+    /// Writes the frame on screen to the file, or to one named after the
+    /// movie and the frame.
+    Snapshot(Option<String>),
+    /// Lines the movie up with one that started at the instant, in
+    /// milliseconds since the epoch.
+    Sync(f64),
+}
+
+impl Command {
+    pub fn parse(command: &str) -> Option<Command> {
+        let mut words = command.split_whitespace();
+
+        let command = match words.next()? {
+            "pause" => Command::Pause,
+            "resume" => Command::Resume,
+            "volume" => Command::Volume(parse_volume(words.next()?)?),
+            "mute" => Command::Mute,
+            "unmute" => Command::Unmute,
+            "lang" => match words.next()? {
+                "off" => Command::Language(None),
+                language => Command::Language(Some(language.to_owned())),
+            },
+            "speed" => Command::Speed(parse_speed(words.next()?)?),
+            "ab" => match words.next() {
+                None => Command::MarkRepeat,
+                Some("off") => Command::Repeat(None),
+                Some(a) => {
+                    let a = a.parse::<f64>().ok().filter(|a| 0. <= *a)?;
+                    let b = words.next()?.parse::<f64>().ok();
+                    let b = b.filter(|b| a < *b)?;
+                    Command::Repeat(Some((a, b)))
+                },
+            },
+            "seek" => Command::Seek(Seek::parse(words.next()?)?),
+            "step" => match words.next() {
+                Some(frames) => Command::Step(frames.parse().ok()?),
+                None => Command::Step(1),
+            },
+            "next" => Command::Step(1),
+            "prev" => Command::Step(-1),
+            "subs" => match words.next()? {
+                "on" => Command::Subtitles(true),
+                "off" => Command::Subtitles(false),
+                _ => return None,
+            },
+            "status" => match words.next()? {
+                "on" => Command::Status(true),
+                "off" => Command::Status(false),
+                _ => return None,
+            },
+            "chapters" => Command::Chapters,
+            "info" => Command::Info,
+            "snapshot" => Command::Snapshot(words.next().map(|f| f.to_owned())),
+            "sync" => {
+                let at = words.next()?.parse().ok();
+                Command::Sync(at.filter(|a: &f64| a.is_finite())?)
+            },
+            _ => return None,
+        };
+
+        // trailing garbage makes the whole command suspicious
+        match words.next() {
+            Some(_) => None,
+            None => Some(command),
+        }
+    }
+}
+
+/// Drains the port, returning the messages on it as text.
+pub fn drain(
+    ns: &NS,
+    port: u32,
+) -> Vec<String> {
+    Port::<String>::new(ns, port)
+        .drain()
+        .map(|message| {
+            // a number written on its own doesn't read as a string
+            message.unwrap_or_else(|data| {
+                data.as_f64().map(|n| n.to_string()).unwrap_or_default()
+            })
+        })
+        .collect()
+}
+
+/// Drains the port, returning the commands that were understood. Anything
+/// else is reported to the terminal and dropped.
+pub fn poll(
+    ns: &NS,
+    port: u32,
+) -> Vec<Command> {
+    drain(ns, port)
+        .into_iter()
+        .filter_map(|message| {
+            let command = Command::parse(&message);
+            if command.is_none() {
+                log::warn!("unknown command: {}", message);
+            }
+
+            command
+        })
+        .collect()
+}
@@ -0,0 +1,31 @@
+//! Picking what the module does by the first argument of the script, so that
+//! the one script does what would otherwise take one for each:
+//!
+//! - `--jukebox` starts the jukebox.
+//! - `--remote` sends a command to a player or to the jukebox.
+//! - `--calibrate` works out the size of a character.
+//! - Anything else goes to the player.
+//!
+//! Each of them is exported on its own as well, for a loader of another
+//! script to call just the one.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    calibrate::calibrate_rs,
+    main_rs,
+    ns::args,
+    remote::remote_rs,
+    NS,
+};
+
+#[wasm_bindgen]
+pub async fn dispatch_rs(ns: &NS) {
+    match args::args(ns).next().map(|a| a.text()).as_deref() {
+        #[cfg(feature = "jukebox")]
+        Some("--jukebox") => crate::jukebox::jukebox_rs(ns).await,
+        Some("--remote") => remote_rs(ns),
+        Some("--calibrate") => calibrate_rs(ns).await,
+        _ => main_rs(ns).await,
+    }
+}
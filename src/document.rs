@@ -0,0 +1,126 @@
+//! Getting at the page that the game is on, for what goes up over it rather
+//! than in the log, and the audio that plays alongside the frames.
+//!
+//! The game charges a script a lot of RAM for so much as naming `document`,
+//! which the module gets around by looking it up at runtime. Some count that
+//! as cheating, so it's behind the `dom` feature, without which there's no
+//! document to be had and whatever goes up over the page falls back to the
+//! log.
+
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "audio")]
+#[wasm_bindgen]
+extern "C" {
+    /// The browser's `HTMLAudioElement`, which can be made without the
+    /// document.
+    #[derive(Clone)]
+    pub type Audio;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(src: &str) -> Audio;
+
+    #[wasm_bindgen(method)]
+    pub fn play(this: &Audio) -> js_sys::Promise;
+
+    #[wasm_bindgen(method)]
+    pub fn pause(this: &Audio);
+
+    #[wasm_bindgen(method, getter, js_name = currentTime)]
+    pub fn current_time(this: &Audio) -> f64;
+
+    #[wasm_bindgen(method, setter, js_name = currentTime)]
+    pub fn set_current_time(
+        this: &Audio,
+        time: f64,
+    );
+
+    #[wasm_bindgen(method, getter)]
+    pub fn duration(this: &Audio) -> f64;
+
+    #[wasm_bindgen(method, setter)]
+    pub fn set_volume(
+        this: &Audio,
+        volume: f64,
+    );
+
+    #[wasm_bindgen(method, setter)]
+    pub fn set_muted(
+        this: &Audio,
+        muted: bool,
+    );
+
+    #[wasm_bindgen(method, setter, js_name = playbackRate)]
+    pub fn set_playback_rate(
+        this: &Audio,
+        rate: f64,
+    );
+}
+
+#[wasm_bindgen]
+extern "C" {
+    pub type Document;
+
+    #[wasm_bindgen(method, js_name = createElement)]
+    pub fn create_element(
+        this: &Document,
+        tag: &str,
+    ) -> Element;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn body(this: &Document) -> Element;
+
+    #[derive(Clone)]
+    pub type Element;
+
+    #[wasm_bindgen(method, js_name = appendChild)]
+    pub fn append_child(
+        this: &Element,
+        child: &Element,
+    );
+
+    #[wasm_bindgen(method, js_name = setAttribute)]
+    pub fn set_attribute(
+        this: &Element,
+        name: &str,
+        value: &str,
+    );
+
+    #[wasm_bindgen(method, setter = innerHTML)]
+    pub fn set_inner_html(
+        this: &Element,
+        html: &str,
+    );
+
+    #[wasm_bindgen(method)]
+    pub fn remove(this: &Element);
+}
+
+/// The document of the page, if the script can get to it.
+#[cfg(feature = "dom")]
+pub fn document() -> Option<Document> {
+    js_sys::Reflect::get(&js_sys::global(), &"document".into())
+        .ok()
+        .filter(|d| d.is_object())
+        .map(|d| d.unchecked_into::<Document>())
+}
+
+#[cfg(not(feature = "dom"))]
+pub fn document() -> Option<Document> {
+    None
+}
+
+/// Puts an empty `tag` up over the page, styled by `style`, which takes
+/// `position: fixed` and the like to go anywhere in particular.
+pub fn overlay(
+    tag: &str,
+    style: &str,
+) -> Option<Element> {
+    let document = document()?;
+
+    let element = document.create_element(tag);
+    element.set_attribute("style", style);
+    document.body().append_child(&element);
+
+    Some(element)
+}
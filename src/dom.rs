@@ -0,0 +1,145 @@
+//! Putting the frames up in a `<pre>` of its own over the page, for
+//! `--render dom`, which leaves the log out of it altogether. Only the rows
+//! that changed since the last frame get redrawn, which goes quicker than
+//! the log redrawing all of them, let alone keeping them in its history.
+
+use crate::{
+    ansi::{
+        self,
+        Style,
+    },
+    document::{
+        self,
+        Document,
+        Element,
+    },
+};
+
+/// Where on the page the frames go, and what they're drawn in.
+const SCREEN_STYLE: &str = "position: fixed; top: 0; left: 0; margin: 0; \
+    z-index: 10000; pointer-events: none; line-height: 1; \
+    font-family: monospace; color: rgb(229,229,229); background: black";
+
+/// The `<pre>` that the frames get put up in, with an element of its own for
+/// every row.
+pub struct Screen {
+    document: Document,
+    pre: Element,
+    rows: Vec<Element>,
+    /// What each of the rows was last drawn with.
+    drawn: Vec<String>,
+}
+
+impl Screen {
+    /// Puts an empty screen up on the page, if the script can get to it.
+    pub fn new() -> Option<Screen> {
+        let document = document::document()?;
+        let pre = document::overlay("pre", SCREEN_STYLE)?;
+
+        Some(Screen {
+            document,
+            pre,
+            rows: vec![],
+            drawn: vec![],
+        })
+    }
+
+    /// Draws `text` over what's up, sparing the rows that are the same.
+    pub fn draw(
+        &mut self,
+        text: &str,
+    ) {
+        let lines = lines(text);
+
+        for (index, line) in lines.iter().enumerate() {
+            if index == self.rows.len() {
+                let row = self.document.create_element("div");
+                self.pre.append_child(&row);
+                self.rows.push(row);
+                self.drawn.push(String::new());
+            }
+
+            if self.drawn[index] != *line {
+                self.rows[index].set_inner_html(line);
+                self.drawn[index] = line.clone();
+            }
+        }
+
+        // the rows past the end of a shorter frame get emptied
+        for index in lines.len() .. self.rows.len() {
+            if !self.drawn[index].is_empty() {
+                self.rows[index].set_inner_html("");
+                self.drawn[index].clear();
+            }
+        }
+    }
+
+    /// How to take the screen off the page, even once it's gone.
+    pub fn remover(&self) -> Box<dyn Fn()> {
+        let pre = self.pre.clone();
+        Box::new(move || pre.remove())
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        self.pre.remove();
+    }
+}
+
+/// Reads the colours of `text` out into spans, a line of HTML at a time.
+fn lines(text: &str) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for (style, run) in ansi::runs(text) {
+        for (index, part) in run.split('\n').enumerate() {
+            if 0 < index {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            if !part.is_empty() {
+                span(&mut line, style, part);
+            }
+        }
+    }
+
+    lines.push(line);
+    lines
+}
+
+fn span(
+    html: &mut String,
+    style: Style,
+    text: &str,
+) {
+    let (foreground, background) = style.colors();
+    let mut css = String::new();
+
+    if let Some(color) = foreground.css() {
+        css += &format!("color:{};", color);
+    }
+    if let Some(color) = background.css() {
+        css += &format!("background-color:{};", color);
+    }
+    if style.bold {
+        css += "font-weight:bold;";
+    }
+
+    if !css.is_empty() {
+        *html += &format!("<span style=\"{}\">", css);
+    }
+
+    for c in text.chars() {
+        match c {
+            '&' => *html += "&amp;",
+            '<' => *html += "&lt;",
+            '>' => *html += "&gt;",
+            c => html.push(c),
+        }
+    }
+
+    if !css.is_empty() {
+        *html += "</span>";
+    }
+}
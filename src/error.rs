@@ -0,0 +1,101 @@
+//! What goes wrong with playing a file, put the way that the terminal shows
+//! it rather than as a panic in the browser console.
+
+use std::{
+    fmt,
+    io,
+};
+
+#[cfg(feature = "base64")]
+use base64::DecodeError;
+#[cfg(feature = "lz4")]
+use lz4_flex::frame::Error as Lz4Error;
+
+use crate::Killed;
+
+pub enum Error {
+    /// The script got killed during an await, which isn't worth reporting.
+    Killed,
+    /// The file is empty, or isn't there at all.
+    Missing(String),
+    /// The file doesn't read as a movie, at some point of it.
+    Unreadable {
+        filename: String,
+        error: io::Error,
+    },
+}
+
+impl Error {
+    pub fn unreadable(
+        filename: &str,
+        error: io::Error,
+    ) -> Error {
+        Error::Unreadable {
+            filename: filename.to_owned(),
+            error,
+        }
+    }
+}
+
+impl From<Killed> for Error {
+    fn from(_: Killed) -> Error {
+        Error::Killed
+    }
+}
+
+/// The error that a decoder handed up inside of an io one, if it's an `E`.
+#[cfg(any(feature = "base64", feature = "lz4"))]
+fn inner<E: std::error::Error + 'static>(error: &io::Error) -> Option<&E> {
+    error.get_ref()?.downcast_ref()
+}
+
+impl fmt::Display for Error {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let (filename, error) = match self {
+            Error::Killed => return write!(f, "the script got killed"),
+            Error::Missing(filename) => {
+                return write!(f, "{} is empty or doesn't exist", filename);
+            },
+            Error::Unreadable { filename, error } => (filename, error),
+        };
+
+        // the decoders hand their errors up wrapped in io ones
+        #[cfg(feature = "base64")]
+        if let Some(e) = inner::<DecodeError>(error) {
+            return write!(
+                f,
+                "{} isn't a movie, as it isn't base64: {}",
+                filename,
+                e,
+            );
+        }
+        #[cfg(feature = "lz4")]
+        if let Some(e) = inner::<Lz4Error>(error) {
+            return write!(
+                f,
+                "{} isn't a movie, or it's corrupt: {}",
+                filename,
+                e,
+            );
+        }
+
+        if error.kind() == io::ErrorKind::UnexpectedEof {
+            write!(f, "{} ends early, so it likely got cut short", filename)?;
+
+            // with how far it got, if that's known
+            match error.get_ref() {
+                Some(e) => write!(f, ": {}", e),
+                None => Ok(()),
+            }
+        }
+        else if error.kind() == io::ErrorKind::InvalidData {
+            write!(f, "{} isn't a movie: {}", filename, error)
+        }
+        else {
+            write!(f, "couldn't read {}: {}", filename, error)
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! The main loop of a script that keeps going until it's done or killed,
+//! which takes the messages off its port, hands them to a step of its work,
+//! and sleeps for as long as the step asks, waking up early for the next
+//! message. A script that gets killed comes out of the loop at the next
+//! sleep, or at whatever the step was waiting on, for the `atExit` handler
+//! to tidy up after it.
+
+use crate::{
+    control,
+    ns::sleep,
+    Killed,
+    NS,
+};
+
+/// What comes after a step of the loop.
+pub enum Next<T> {
+    /// Another step, after this many milliseconds, or as soon as a message
+    /// comes in.
+    Wait(f64),
+    /// Another step, right away, for a step that's already had its wait.
+    Now,
+    /// Nothing, with the loop coming out with this.
+    Done(T),
+}
+
+/// Runs `step` over and over, each time with the messages that came in on
+/// `port` since the last, as text.
+pub async fn run_loop<T>(
+    ns: &NS,
+    port: Option<u32>,
+    mut step: impl AsyncFnMut(Vec<String>) -> Result<Next<T>, Killed>,
+) -> Result<T, Killed> {
+    loop {
+        let messages = port.map(|p| control::drain(ns, p)).unwrap_or_default();
+
+        // even a step that took all of its time leaves the page a turn
+        match (step(messages).await?, port) {
+            (Next::Done(done), _) => return Ok(done),
+            (Next::Now, _) => {},
+            (Next::Wait(ms), Some(port)) => {
+                sleep::sleep_or_message(ns, ms, port).await?;
+            },
+            (Next::Wait(ms), None) => sleep::sleep_ms(ns, ms).await?,
+        }
+    }
+}
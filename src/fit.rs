@@ -0,0 +1,152 @@
+//! Keeping the frames whole once the tail window has been resized by hand,
+//! for `--fit`, which either centres them in the window as it is, or sizes
+//! the window back for them.
+
+use std::borrow::Cow;
+
+use crate::{
+    ns::ui,
+    performance_now,
+    tail::{
+        self,
+        CharSize,
+    },
+    NS,
+};
+
+/// How long the size of the tail window is gone by before it's looked up
+/// again, since that's a `getRunningScript` for every frame otherwise.
+const CHECK_EVERY_MS: f64 = 500.;
+
+#[derive(Clone, Copy, Default)]
+pub enum Fit {
+    /// Centring the frames in however large the window is.
+    #[default]
+    Letterbox,
+    /// Sizing the window back to fit the frames.
+    Window,
+}
+
+impl Fit {
+    pub fn parse(fit: &str) -> Option<Fit> {
+        match fit {
+            "letterbox" => Some(Fit::Letterbox),
+            "window" => Some(Fit::Window),
+            _ => None,
+        }
+    }
+}
+
+/// Fits the frames into the tail window, going by the size it was last seen
+/// at, for up to [`CHECK_EVERY_MS`].
+pub struct Fitter {
+    fit: Fit,
+    char_size: CharSize,
+    size: Option<(f64, f64)>,
+    checked: Option<f64>,
+}
+
+impl Fitter {
+    pub fn new(
+        fit: Fit,
+        char_size: CharSize,
+    ) -> Fitter {
+        Fitter {
+            fit,
+            char_size,
+            size: None,
+            checked: None,
+        }
+    }
+
+    /// The size of the tail window, looked up again once it's gone a while.
+    fn size(
+        &mut self,
+        ns: &NS,
+    ) -> Option<(f64, f64)> {
+        let now = performance_now();
+        if self
+            .checked
+            .is_none_or(|checked| now - checked >= CHECK_EVERY_MS)
+        {
+            self.size = tail::size(ns);
+            self.checked = Some(now);
+        }
+
+        self.size
+    }
+
+    /// Fits `text`, of `columns` by `rows` characters, into the tail window
+    /// as large as it is, which doesn't go for a window smaller than the
+    /// text.
+    pub fn fit<'a>(
+        &mut self,
+        ns: &NS,
+        columns: u32,
+        rows: u32,
+        text: &'a str,
+    ) -> Cow<'a, str> {
+        let Some((width, height)) = self.size(ns)
+        else {
+            return Cow::Borrowed(text);
+        };
+
+        match self.fit {
+            Fit::Letterbox => {
+                let (fit_columns, fit_rows) =
+                    self.char_size.fits(width, height);
+                let spare_columns = fit_columns.saturating_sub(columns);
+                let spare_rows = fit_rows.saturating_sub(rows);
+
+                letterbox(text, spare_columns, spare_rows)
+            },
+            Fit::Window => {
+                let (tail_width, tail_height) =
+                    self.char_size.tail_size(columns, rows);
+
+                // the size as the game has it can be off by a fraction
+                if (width - tail_width as f64).abs() >= 1.
+                    || (height - tail_height as f64).abs() >= 1.
+                {
+                    ui::resize_tail(ns, tail_width, tail_height);
+                    self.size = Some((tail_width as f64, tail_height as f64));
+                }
+
+                Cow::Borrowed(text)
+            },
+        }
+    }
+}
+
+/// Centres `text` in `spare_columns` and `spare_rows` more than it takes up,
+/// padding it out below as well, since the log keeps to the bottom and
+/// whatever taller frame went up before would show above it otherwise.
+fn letterbox(
+    text: &str,
+    spare_columns: u32,
+    spare_rows: u32,
+) -> Cow<'_, str> {
+    if spare_columns < 2 && spare_rows < 2 {
+        return Cow::Borrowed(text);
+    }
+
+    let margin = " ".repeat(spare_columns as usize / 2);
+    let mut fitted = "\n".repeat(spare_rows as usize / 2);
+
+    for line in text.split_inclusive('\n') {
+        fitted += &margin;
+        fitted += line;
+    }
+
+    // a line that's left empty at the very end doesn't get shown, so the
+    // padding below is of spaces
+    let bottom = (spare_rows - spare_rows / 2) as usize;
+    if bottom > 0 {
+        if !fitted.ends_with('\n') {
+            fitted.push('\n');
+        }
+        fitted += &vec![" "; bottom].join("\n");
+    }
+
+    Cow::Owned(fitted)
+}
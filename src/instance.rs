@@ -0,0 +1,28 @@
+//! Telling apart players started with `--instance`, so that several movies
+//! can play at once, each in a tail window of its own.
+//!
+//! Every instance gets ports of its own, picked by hashing its name, along
+//! with state files of its own. `remote.js` hashes names the same way.
+
+/// How far apart the ports of any two instances are, which leaves room for
+/// every port that a player uses.
+const PORT_STRIDE: u32 = 100;
+
+/// How many sets of ports the names get hashed into.
+const PORT_SETS: u32 = 10_000;
+
+/// The 32-bit FNV-1a hash of the name.
+fn hash(name: &str) -> u32 {
+    name.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+/// The port of the instance that stands in for `port` of a player without
+/// one. None of them lands on the ports of the latter.
+pub fn port(
+    name: &str,
+    port: u32,
+) -> u32 {
+    port + PORT_STRIDE * (1 + hash(name) % PORT_SETS)
+}
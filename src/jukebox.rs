@@ -0,0 +1,146 @@
+//! The jukebox, which plays the files queued up on it one after the other,
+//! each through a player of its own.
+//!
+//! It ships in the same script as the player, and gets started with
+//! `run bitoxide.js --jukebox [options]`, handing the options down to every
+//! player. Files get queued by writing `queue movie.txt` to its port, or by
+//! appending them to its queue file a line at a time, while `skip` and
+//! `clear` drop the file playing and the files queued.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::Rc,
+};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    event_loop::{
+        self,
+        Next,
+    },
+    logger,
+    ns::{
+        args,
+        processes::Child,
+        SafeNs,
+    },
+    panic,
+    script,
+    storage,
+    NS,
+};
+
+/// The port that the jukebox listens on, next to that of the player.
+pub const JUKEBOX_PORT: u32 = 21;
+
+/// The file that gets emptied into the queue as the jukebox comes across it.
+const QUEUE_FILE: &str = "jukebox.queue.txt";
+
+/// How often the port, the queue file, and the player get checked, in
+/// milliseconds.
+const POLL_MS: u32 = 250;
+
+enum Command {
+    Queue(String),
+    /// Stops the file playing, going on to the next.
+    Skip,
+    /// Drops every file in the queue, leaving the one playing be.
+    Clear,
+}
+
+impl Command {
+    fn parse(command: &str) -> Option<Command> {
+        let command = command.trim();
+
+        match command.split_once(' ') {
+            Some(("queue", filename)) => {
+                Some(Command::Queue(filename.trim().to_owned()))
+            },
+            None if command == "skip" => Some(Command::Skip),
+            None if command == "clear" => Some(Command::Clear),
+            _ => None,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub async fn jukebox_rs(ns: &NS) {
+    script::run(ns, jukebox(ns)).await;
+}
+
+async fn jukebox(ns: &NS) {
+    panic::install();
+    logger::install(logger::Sink::Terminal);
+    ns.disableLog("ALL");
+
+    // past the --jukebox, everything goes to the players
+    let player_args = args::args(ns).skip(1).collect::<Vec<_>>();
+    let safe_ns = SafeNs::new(ns.clone());
+    let script = ns.getScriptName();
+
+    let mut queue = VecDeque::new();
+    // the player, and the file that it plays
+    let playing: Rc<RefCell<Option<(Child, String)>>> = Rc::default();
+
+    // the player would play on with nothing left to stop it otherwise
+    let handler = {
+        let playing = playing.clone();
+
+        Closure::once_into_js(move || {
+            if let Some((player, _)) = playing.borrow().as_ref() {
+                player.kill();
+            }
+        })
+    };
+    ns.atExit(&handler);
+
+    // a command gets answered right away, rather than at the next check
+    let _ = event_loop::run_loop(ns, Some(JUKEBOX_PORT), async |messages| {
+        let mut playing = playing.borrow_mut();
+
+        for message in messages {
+            match Command::parse(&message) {
+                Some(Command::Queue(filename)) => queue.push_back(filename),
+                Some(Command::Skip) => {
+                    if let Some((player, _)) = playing.as_ref() {
+                        player.kill();
+                    }
+                },
+                Some(Command::Clear) => queue.clear(),
+                None => log::warn!("unknown command: {}", message),
+            }
+        }
+
+        let queued = storage::read_lines(ns, QUEUE_FILE);
+        if !queued.is_empty() {
+            queue.extend(queued);
+            storage::clear(ns, QUEUE_FILE);
+        }
+
+        if playing.as_ref().is_some_and(|(player, _)| !player.is_running()) {
+            *playing = None;
+        }
+
+        if playing.is_none() {
+            if let Some(filename) = queue.pop_front() {
+                let mut args = vec![JsValue::from_str(&filename)];
+                args.extend(player_args.iter().map(JsValue::from));
+
+                // a player that didn't start likely lacked the RAM
+                match Child::run(&safe_ns, &script, 1, &args) {
+                    Ok(Some(player)) => {
+                        log::info!("now playing {}", filename);
+                        *playing = Some((player, filename));
+                    },
+                    Ok(None) => log::error!("couldn't play {}", filename),
+                    Err(e) => log::error!("couldn't play {}: {}", filename, e),
+                }
+            }
+        }
+
+        Ok(Next::<()>::Wait(POLL_MS.into()))
+    })
+    .await;
+}
@@ -1,141 +1,1625 @@
-use wasm_bindgen::{
-    prelude::*,
-    JsValue,
+pub mod animation;
+mod ansi;
+mod audio;
+mod benchmark;
+mod calibrate;
+mod chapters;
+#[cfg(feature = "audio")]
+mod chiptune;
+pub mod clock;
+mod control;
+mod dispatch;
+pub mod document;
+mod dom;
+mod error;
+pub mod event_loop;
+mod fit;
+mod instance;
+#[cfg(feature = "jukebox")]
+mod jukebox;
+pub mod logger;
+mod lyrics;
+mod metadata;
+mod now_playing;
+pub mod ns;
+mod osd;
+mod panic;
+mod parts;
+mod picture;
+mod playback;
+mod quality;
+mod radio;
+mod remote;
+mod render;
+mod resume;
+mod sanitize;
+mod screensaver;
+pub mod script;
+mod shutdown;
+mod stats;
+mod status;
+pub mod storage;
+mod subtitles;
+mod tail;
+mod theme;
+mod zoom;
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+use bitburner_video::{
+    self as movie,
+    Decoded,
+    Decompressor,
+};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    benchmark::{
+        Benchmark,
+        Step,
+    },
+    chapters::Chapters,
+    clock::{
+        Clock,
+        MonotonicClock,
+        Schedule,
+        WallClock,
+    },
+    error::Error,
+    event_loop::Next,
+    fit::{
+        Fit,
+        Fitter,
+    },
+    lyrics::Lyrics,
+    metadata::Metadata,
+    ns::{
+        args::ScriptArg,
+        flags::Flags,
+        ram,
+        ui,
+    },
+    parts::{
+        Contents,
+        Source,
+    },
+    picture::Adjustment,
+    playback::{
+        Panel,
+        Playback,
+    },
+    quality::Quality,
+    shutdown::Shutdown,
+    stats::Stats,
+    subtitles::Subtitles,
+    tail::CharSize,
+    theme::Tint,
 };
 
-// thank you github.com/paulcdejean
+pub use crate::ns::NS;
+
 #[wasm_bindgen]
 extern "C" {
-    pub type NS;
-
-    #[wasm_bindgen(method)]
-    fn print(
-        this: &NS,
-        print: &str,
-    );
-
-    #[wasm_bindgen(method)]
-    fn clearLog(
-        this: &NS,
-    );
-
-    #[wasm_bindgen(method)]
-    fn tprint(
-        this: &NS,
-        print: &str,
-    );
-
-    #[wasm_bindgen(method)]
+    /// `performance.now()`, which unlike `Date.now()` has resolution finer
+    /// than a millisecond, and never jumps when the system clock gets set.
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+/// The script got killed during an await, which leaves nothing to do but
+/// return. The game rejects whatever the script awaits on then, and the
+/// `atExit` handler does the tidying up.
+pub struct Killed;
+
+/// Formats seconds as `m:ss`, or `h:mm:ss` for anything an hour or longer.
+fn format_time(secs: f64) -> String {
+    let secs = secs.max(0.) as u64;
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    match hours {
+        0 => format!("{}:{:02}", minutes, secs),
+        _ => format!("{}:{:02}:{:02}", hours, minutes, secs),
+    }
+}
+
+/// Parses seconds given as `90`, `1:30`, or `1:01:30`, the way that
+/// [`format_time`] puts them.
+fn parse_time(time: &str) -> Option<f64> {
+    let parts = time.split(':').collect::<Vec<_>>();
+    let (secs, whole) = parts.split_last()?;
+
+    if 2 < whole.len() {
+        return None;
+    }
+
+    let secs = secs.parse::<f64>().ok().filter(|s| s.is_finite() && 0. <= *s)?;
+
+    // the hours and the minutes are whole
+    whole.iter().rev().zip([60., 3600.]).try_fold(
+        secs,
+        |total, (part, scale)| {
+            Some(total + part.parse::<u32>().ok()? as f64 * scale)
+        },
+    )
+}
+
+#[cfg(feature = "base64")]
+type Base64Reader<'a> = base64::read::DecoderReader<
+    'static,
+    base64::engine::GeneralPurpose,
+    Source<'a>,
+>;
+
+type MovieReader<'a> = std::io::BufReader<
+    Decompressor<std::io::BufReader<Encoded<'a>>>,
+>;
+
+/// The compressed movie as the base64 of it gets decoded, or the movie itself
+/// if it's in plain text.
+enum Encoded<'a> {
+    // the decoder keeps its buffers inline
+    #[cfg(feature = "base64")]
+    Base64(Box<Base64Reader<'a>>),
+    Plain(Source<'a>),
+}
+
+impl std::io::Read for Encoded<'_> {
     fn read(
-        ns: &NS,
-        filename: &str,
-    ) -> String;
-
-    #[wasm_bindgen(method)]
-    fn disableLog(
-        ns: &NS,
-        func: &str,
-    );
-
-    #[wasm_bindgen(method)]
-    async fn sleep(
-        ns: &NS,
-        millis: u32,
-    );
-
-    #[wasm_bindgen(method)]
-    fn resizeTail(
-        ns: &NS,
-        width: u32,
-        height: u32,
-    );
-}
-
-pub fn get_attribute<T>(
-    object: &JsValue,
-    field_name: &str,
-    mapper: impl Fn(&JsValue) -> Option<T>,
-) -> Result<Option<T>, JsValue> {
-    js_sys::Reflect::get(object, &JsValue::from_str(field_name))
-        .map(|x| mapper(&x))
+        &mut self,
+        buf: &mut [u8],
+    ) -> std::io::Result<usize> {
+        match self {
+            #[cfg(feature = "base64")]
+            Encoded::Base64(r) => r.read(buf),
+            Encoded::Plain(r) => r.read(buf),
+        }
+    }
+}
+
+/// Starts reading the decompressed movie out of the contents of the file.
+/// Both the base64 and the compression get decoded a bit at a time as the
+/// movie gets read, so that only the file itself, or the part of it being
+/// read, has to fit in memory as a whole.
+fn open_movie<'a>(
+    ns: &'a NS,
+    contents: &'a Contents,
+) -> std::io::Result<MovieReader<'a>> {
+    let source = contents.reader(ns);
+
+    let decompressor = match contents.is_text() {
+        true => {
+            let plain = std::io::BufReader::new(Encoded::Plain(source));
+            Decompressor::Plain(plain)
+        },
+        #[cfg(feature = "base64")]
+        false => {
+            let base64 = base64::read::DecoderReader::new(
+                source,
+                &base64::prelude::BASE64_STANDARD,
+            );
+            let base64 = Encoded::Base64(Box::new(base64));
+            let base64 = std::io::BufReader::new(base64);
+            Decompressor::new(base64)?
+        },
+        #[cfg(not(feature = "base64"))]
+        false => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "it isn't #TEXT, and this build only plays those",
+            ));
+        },
+    };
+
+    Ok(std::io::BufReader::new(decompressor))
+}
+
+/// Reads the chunks of the movie, which one in plain text has none of, as a
+/// frame written by hand can just as well start with a `#` as a chunk does.
+fn read_chunks(
+    contents: &Contents,
+    reader: &mut MovieReader<'_>,
+) -> std::io::Result<Vec<movie::Chunk>> {
+    match contents.is_text() {
+        true => Ok(vec![]),
+        false => movie::read_chunks(reader),
+    }
+}
+
+/// Starts reading the movie over from its first frame.
+fn open_frames<'a>(
+    ns: &'a NS,
+    contents: &'a Contents,
+    width: u32,
+    height: u32,
+) -> std::io::Result<movie::Frames<MovieReader<'a>>> {
+    let mut reader = open_movie(ns, contents)?;
+    movie::read_header(&mut reader)?;
+    read_chunks(contents, &mut reader)?;
+
+    Ok(movie::Frames::new(reader, width, height))
+}
+
+/// What `--help` prints.
+const USAGE: &str = "\
+usage: run bitoxide.js [options] <file>... [--playlist <file>]
+       run bitoxide.js --jukebox [options]
+       run bitoxide.js --remote [--port <port> | --instance <name> |
+                                 --jukebox] <command>...
+       run bitoxide.js --calibrate
+
+playback:
+  --loop                 start over once the end is reached
+  --shuffle              play the files in a random order
+  --gap <ms>             wait between the files
+  --speed <factor>       play faster or slower, from 0.25 to 4
+  --paused               start out paused, on the first frame
+  --manual               only move on through next and prev, as slides
+  --resume               pick up from where the last playback left off
+  --start <time>         start this far in, as 90 or 1:30
+  --preroll <frames>     decode this many frames before the first goes up
+  --max-memory <mb>      decode fewer frames ahead, to keep under this much
+  --benchmark            play as fast as it goes, timing each frame
+  --sync-at <epoch-ms>   start at the instant, or where it'd be by now
+  --port <port>          listen for commands on this port, instead of 20
+  --instance <name>      keep ports and state files apart from other players
+  --verbose              tell what happens with every frame in the terminal
+
+audio:
+  --volume <0-1>         set the volume
+  --mute                 start out muted
+
+subtitles:
+  --lang <code>          show the subtitles in this language
+  --subs-color <color>   white, yellow, cyan, green, magenta, red, or blue
+  --subs-position <pos>  top or bottom
+  --subs-width <chars>   wrap the subtitles at this width
+
+display:
+  --no-status            hide the status line under the frames
+  --render <mode>        ansi, react for the colours to be drawn as elements,
+                         or dom for a screen of its own over the page
+  --char-size <w>x<h>    size the tail by characters this many pixels large
+  --pos <x>,<y>          move the tail this many pixels from the top left
+  --no-tail              don't open the tail, leaving it to be opened by hand
+  --terminal             play in the terminal rather than in the tail, for a
+                         movie of a few frames a second
+  --title <title>        title the tail, rather than by the movie
+  --zoom <times>         put every character up this many times over
+  --zoom-rows            put every row up as many times over as well
+  --fit <fit>            once the tail is resized, letterbox to centre the
+                         frames in it, or window to size it back
+  --calibrate            work out the size of a character, for the tail
+  --ram                  tell what the script takes so much RAM for
+  --theme-tint           tint the greys with the primary colour of the theme
+  --brightness <-1-1>    brighten or darken the characters by this much
+  --contrast <factor>    up the contrast of the characters this many times
+  --no-attribution       don't show the attribution as the movie starts
+
+once everything has played:
+  --exit-after <secs>    wait before exiting, with the last frame still up
+  --keep-last-frame      leave the last frame up, rather than clearing it
+  --close-tail-on-end    close the tail window
+  --screensaver <kind>   put up a starfield or matrix until killed, which is
+                         all that plays without a file
+  --help                 print this";
+
+/// How every file gets played, as given by the script arguments.
+struct Options {
+    volume: f64,
+    muted: bool,
+    language: Option<String>,
+    subs_style: subtitles::Style,
+    /// Whether to show the attribution of the movie as it starts.
+    attribution: bool,
+    /// The name that tells the player apart from others playing at once.
+    instance: Option<String>,
+    port: u32,
+    now_playing_port: u32,
+    speed: f64,
+    looping: bool,
+    /// Whether to start out paused, on the first frame.
+    paused: bool,
+    /// Whether the frames only move on when stepped through.
+    manual: bool,
+    /// Whether to pick up from where the last playback of the movie left
+    /// off.
+    resume: bool,
+    /// Where to start the playback from otherwise, in seconds.
+    start: Option<f64>,
+    /// How many frames to decode before the clock starts.
+    preroll: usize,
+    /// The most bytes that the movie and the frames decoded ahead of it can
+    /// take up, past which the frames only get decoded as they're due.
+    max_memory: Option<usize>,
+    /// Whether to play without waiting for the frames to be due, timing how
+    /// long each one takes.
+    benchmark: bool,
+    /// The instant to start at, in milliseconds since the epoch.
+    sync_at: Option<f64>,
+    /// Whether to show the status line under the frames.
+    status: bool,
+    /// How large a character of the tail window is.
+    char_size: CharSize,
+    /// What the tail window gets titled, rather than the title of the movie.
+    title: Option<String>,
+    /// How the frames get put up in the log.
+    render: render::Mode,
+    /// Whether the greys get tinted with the colour of the theme.
+    theme_tint: bool,
+    /// How much brighter the characters get, from -1 to 1.
+    brightness: f64,
+    /// How many times the contrast of the characters gets upped.
+    contrast: f64,
+    /// What becomes of the frames once the tail window gets resized.
+    fit: Fit,
+    /// How many times over every character gets put up.
+    zoom: u32,
+    /// Whether every row gets put up as many times over as well.
+    zoom_rows: bool,
+}
+
+/// The arguments of the player, as `ns.flags` reads them into [`Options`].
+/// What's left empty, or at a number that can't be given, wasn't given.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Args {
+    volume: f64,
+    mute: bool,
+    #[serde(rename = "loop")]
+    looping: bool,
+    paused: bool,
+    manual: bool,
+    resume: bool,
+    benchmark: bool,
+    verbose: bool,
+    status: bool,
+    no_status: bool,
+    help: bool,
+    h: bool,
+    start: String,
+    preroll: f64,
+    max_memory: f64,
+    sync_at: String,
+    speed: f64,
+    port: f64,
+    instance: String,
+    no_attribution: bool,
+    calibrate: bool,
+    ram: bool,
+    no_tail: bool,
+    terminal: bool,
+    theme_tint: bool,
+    zoom: f64,
+    zoom_rows: bool,
+    fit: String,
+    brightness: f64,
+    contrast: f64,
+    render: String,
+    pos: String,
+    title: String,
+    char_size: String,
+    lang: String,
+    subs_color: String,
+    subs_position: String,
+    subs_width: f64,
+    shuffle: bool,
+    gap: f64,
+    exit_after: f64,
+    keep_last_frame: bool,
+    close_tail_on_end: bool,
+    screensaver: String,
+    playlist: Vec<String>,
+    /// The files to play.
+    #[serde(rename = "_")]
+    files: Vec<ScriptArg>,
+}
+
+/// Passes if `valid`, or tells what the flag needs.
+fn needs(
+    valid: bool,
+    message: &str,
+) -> Result<(), String> {
+    valid.then_some(()).ok_or_else(|| message.to_owned())
+}
+
+/// Parses `text` by `parse`, unless it wasn't given.
+fn given<T>(
+    text: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<Option<T>> {
+    match text.is_empty() {
+        true => Some(None),
+        false => parse(text).map(Some),
+    }
+}
+
+/// Parses the pixels of `--pos`, as `100,200`.
+fn parse_position(position: &str) -> Option<(f64, f64)> {
+    let (x, y) = position.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Parses an epoch time in milliseconds, for `--sync-at`.
+fn parse_instant(instant: &str) -> Option<f64> {
+    instant.parse().ok().filter(|i: &f64| i.is_finite())
+}
+
+/// The flags of the player, each with its default and what it needs to be.
+fn player_flags() -> Flags<Args> {
+    let whole = |n: f64| n.fract() == 0.;
+
+    Flags::<Args>::new()
+        .flag("volume", 1.)
+        .flag("mute", false)
+        .flag("loop", false)
+        .flag("paused", false)
+        .flag("manual", false)
+        .flag("resume", false)
+        .flag("benchmark", false)
+        .flag("verbose", false)
+        .flag("status", false)
+        .flag("no-status", false)
+        .flag("help", false)
+        .flag("h", false)
+        .flag("start", "")
+        .flag("preroll", 0.)
+        .flag("max-memory", 0.)
+        .flag("sync-at", "")
+        .flag("speed", 1.)
+        .flag("port", 0.)
+        .flag("instance", "")
+        .flag("no-attribution", false)
+        .flag("calibrate", false)
+        .flag("ram", false)
+        .flag("no-tail", false)
+        .flag("terminal", false)
+        .flag("theme-tint", false)
+        .flag("zoom", 1.)
+        .flag("zoom-rows", false)
+        .flag("fit", "")
+        .flag("brightness", 0.)
+        .flag("contrast", 1.)
+        .flag("render", "")
+        .flag("pos", "")
+        .flag("title", "")
+        .flag("char-size", "")
+        .flag("lang", "")
+        .flag("subs-color", "")
+        .flag("subs-position", "")
+        .flag("subs-width", 0.)
+        .flag("shuffle", false)
+        .flag("gap", 0.)
+        .flag("exit-after", 0.)
+        .flag("keep-last-frame", false)
+        .flag("close-tail-on-end", false)
+        .flag("screensaver", "")
+        .flag("playlist", Vec::new())
+        .check(|a| {
+            needs(
+                control::clamp_volume(a.volume).is_some(),
+                "--volume needs a number from 0 to 1",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.start, parse_time).is_some(),
+                "--start needs a time like 90 or 1:30",
+            )
+        })
+        .check(|a| {
+            needs(0. <= a.preroll, "--preroll needs a number of frames")
+        })
+        .check(|a| {
+            needs(
+                0. <= a.max_memory,
+                "--max-memory needs a number of megabytes",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.sync_at, parse_instant).is_some(),
+                "--sync-at needs an epoch time in ms",
+            )
+        })
+        .check(|a| {
+            needs(
+                control::SPEEDS.contains(&a.speed),
+                "--speed needs a number from 0.25 to 4",
+            )
+        })
+        .check(move |a| {
+            needs(
+                a.port == 0. || (1. <= a.port && whole(a.port)),
+                "--port needs the number of a port",
+            )
+        })
+        .check(move |a| {
+            needs(
+                whole(a.zoom) && (1. ..= 8.).contains(&a.zoom),
+                "--zoom needs a whole number from 1 to 8",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.fit, Fit::parse).is_some(),
+                "--fit needs either letterbox or window",
+            )
+        })
+        .check(|a| {
+            needs(
+                (-1. ..= 1.).contains(&a.brightness),
+                "--brightness needs a number from -1 to 1",
+            )
+        })
+        .check(|a| {
+            needs(
+                a.contrast.is_finite() && 0. <= a.contrast,
+                "--contrast needs a number of at least 0",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.render, render::Mode::parse).is_some(),
+                "--render needs ansi, react, or dom",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.pos, parse_position).is_some(),
+                "--pos needs the pixels as 100,200",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.char_size, CharSize::parse).is_some(),
+                "--char-size needs the pixels as 10x30",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.subs_color, subtitles::parse_color).is_some(),
+                "--subs-color needs one of white, yellow, cyan, green, \
+                 magenta, red, or blue",
+            )
+        })
+        .check(|a| {
+            needs(
+                given(&a.subs_position, subtitles::Position::parse).is_some(),
+                "--subs-position needs either top or bottom",
+            )
+        })
+        .check(|a| {
+            needs(
+                a.subs_width == 0. || 1. <= a.subs_width,
+                "--subs-width needs a positive number",
+            )
+        })
+        .check(|a| needs(0. <= a.gap, "--gap needs a number of milliseconds"))
+        .check(|a| {
+            needs(0. <= a.exit_after, "--exit-after needs a number of seconds")
+        })
+        .check(|a| {
+            needs(
+                given(&a.screensaver, screensaver::Kind::parse).is_some(),
+                "--screensaver needs starfield or matrix",
+            )
+        })
 }
 
 #[wasm_bindgen]
 pub async fn main_rs(ns: &NS) {
-    use base64::engine::Engine as _;
-    use std::io::BufRead as _;
-
-    let args = get_attribute(ns, "args", |a| Some(js_sys::Array::from(a)))
-        .unwrap()
-        .unwrap();
-    let mut args_iter = args.iter().map(|a| a.as_string().unwrap());
+    script::run(ns, player(ns)).await;
+}
 
+/// The player, which is what the script is for unless told otherwise.
+async fn player(ns: &NS) {
+    panic::install();
+    logger::install(logger::Sink::Terminal);
     ns.disableLog("ALL");
 
-    let filename = args_iter.next().unwrap();
+    let args = match player_flags().parse(ns) {
+        Ok(args) => args,
+        Err(e) => {
+            ns.tprint(&e);
+            ns.tprint("see --help for the arguments");
+            return;
+        },
+    };
 
-    // open a file
-    let file_contents = ns.read(&filename);
-    if file_contents.is_empty() {
+    if args.help || args.h {
+        ns.tprint(USAGE);
         return;
     }
+    if args.verbose {
+        log::set_max_level(log::LevelFilter::Debug);
+    }
+
+    // the flags have all been checked, so what's empty is all that's left
+    // out of these
+    let mut filenames = args.files.iter().map(|f| f.text()).collect::<Vec<_>>();
+    for playlist in &args.playlist {
+        // a filename per line, skipping blank lines and # comments
+        filenames.extend(storage::read_lines(ns, playlist));
+    }
+
+    let port_given = args.port != 0.;
+    let shuffle = args.shuffle;
+    let gap_ms = args.gap as u32;
+    let exit_after_ms = (args.exit_after * 1000.) as u32;
+    let keep_last_frame = args.keep_last_frame;
+    let close_tail_on_end = args.close_tail_on_end;
+    let screensaver =
+        given(&args.screensaver, screensaver::Kind::parse).flatten();
+
+    let mut subs_style = subtitles::Style::default();
+    if let Some(Some(color)) = given(&args.subs_color, subtitles::parse_color) {
+        subs_style.color = color;
+    }
+    let position = given(&args.subs_position, subtitles::Position::parse);
+    if let Some(Some(position)) = position {
+        subs_style.position = position;
+    }
+    if 1. <= args.subs_width {
+        subs_style.max_width = Some(args.subs_width as usize);
+    }
+    let mut options = Options {
+        volume: control::clamp_volume(args.volume).unwrap_or(1.),
+        muted: args.mute,
+        language: Some(args.lang).filter(|l| !l.is_empty()),
+        subs_style,
+        attribution: !args.no_attribution,
+        instance: Some(args.instance).filter(|i| !i.is_empty()),
+        port: match port_given {
+            true => args.port as u32,
+            false => control::CONTROL_PORT,
+        },
+        now_playing_port: now_playing::NOW_PLAYING_PORT,
+        speed: args.speed,
+        looping: args.looping,
+        paused: args.paused,
+        manual: args.manual,
+        resume: args.resume,
+        start: given(&args.start, parse_time).flatten(),
+        preroll: args.preroll as usize,
+        max_memory: (0. < args.max_memory)
+            .then_some((args.max_memory * stats::MEGABYTE as f64) as usize),
+        benchmark: args.benchmark,
+        sync_at: given(&args.sync_at, parse_instant).flatten(),
+        status: args.status || !args.no_status,
+        char_size: CharSize::default(),
+        title: Some(args.title).filter(|t| !t.is_empty()),
+        render: match args.terminal {
+            true => render::Mode::Terminal,
+            false => {
+                given(&args.render, render::Mode::parse)
+                    .flatten()
+                    .unwrap_or_default()
+            },
+        },
+        theme_tint: args.theme_tint,
+        brightness: args.brightness,
+        contrast: args.contrast,
+        fit: given(&args.fit, Fit::parse).flatten().unwrap_or_default(),
+        zoom: args.zoom as u32,
+        zoom_rows: args.zoom_rows,
+    };
+    let char_size = given(&args.char_size, CharSize::parse).flatten();
+    let tail_position = given(&args.pos, parse_position).flatten();
+    let open_tail = !args.no_tail && !args.terminal;
+    let calibrating = args.calibrate;
+    let show_ram = args.ram;
 
-    // decode base64 then lz4
-    let decoded = base64::prelude::BASE64_STANDARD.decode(&*file_contents);
-    let decoded = decoded.unwrap();
-    let decoder = std::io::BufReader::new(lz4_flex::frame::FrameDecoder::new(std::io::Cursor::new(decoded)));
-    let mut decoder = decoder.lines();
+    if let Some(name) = options.instance.as_deref() {
+        // a port given outright still gets the commands
+        if !port_given {
+            options.port = instance::port(name, control::CONTROL_PORT);
+        }
 
-    let framerate = decoder.next().unwrap().unwrap().parse::<f64>().unwrap();
+        options.now_playing_port =
+            instance::port(name, now_playing::NOW_PLAYING_PORT);
+    }
 
-    let dimensions = decoder.next().unwrap().unwrap();
-    let mut dimensions = dimensions.split(" ");
-    let x = dimensions.next().unwrap().parse::<u32>().unwrap();
-    let y = dimensions.next().unwrap().parse::<u32>().unwrap();
- 
-    let mut buffer = String::new();
-    let mut line_count = 0;
+    // nothing shows until the tail is open
+    if open_tail {
+        ui::open_tail(ns);
+    }
 
-    let mut first_print = None;
-    let mut frame_count = 0;
+    if calibrating {
+        let _ = calibrate::calibrate(ns).await;
+        return;
+    }
 
-    for line in decoder {
-        let line = match line {
-            Ok(l) => l,
-            Err(e) => {
-                ns.tprint(&format!("{e:?}"));
-                return;
+    let ram = match show_ram || log::log_enabled!(log::Level::Debug) {
+        true => ram::Report::new(ns).map(|r| r.breakdown()),
+        false => None,
+    };
+    if show_ram {
+        ns.tprint(ram.as_deref().unwrap_or("couldn't look into the script"));
+        return;
+    }
+    if let Some(ram) = ram {
+        log::debug!("{}", ram);
+    }
+
+    options.char_size = char_size.unwrap_or_else(|| CharSize::detect(ns));
+
+    if filenames.is_empty() {
+        match screensaver {
+            Some(kind) => {
+                let _ = screensaver::run(ns, kind, options.char_size).await;
             },
+            None => {
+                ns.tprint("needs the file to play, or a --playlist; see --help")
+            },
+        }
+
+        return;
+    }
+
+    if shuffle {
+        // Fisher-Yates
+        for i in (1 .. filenames.len()).rev() {
+            let j = (js_sys::Math::random() * (i + 1) as f64) as usize;
+            filenames.swap(i, j.min(i));
+        }
+    }
+
+    // a playlist starts over as a whole rather than file by file
+    let looping_playlist = options.looping && 1 < filenames.len();
+    if looping_playlist {
+        options.looping = false;
+    }
+
+    let shutdown =
+        Shutdown::register(ns, options.port, options.now_playing_port);
+
+    if let Some((x, y)) = tail_position {
+        ui::move_tail(ns, x, y);
+    }
+
+    loop {
+        for (index, filename) in filenames.iter().enumerate() {
+            if index != 0 && 0 < gap_ms && ns.sleep(gap_ms).await.is_err() {
+                return;
+            }
+
+            // a file that won't play doesn't hold up the rest of them
+            match play_file(ns, filename, &options, &shutdown).await {
+                Ok(()) => {},
+                Err(Error::Killed) => return,
+                Err(e) => log::error!("{}", e),
+            }
+
+            panic::set_frame(None);
+        }
+
+        if !looping_playlist {
+            break;
+        }
+
+        if ns.sleep(gap_ms).await.is_err() {
+            return;
+        }
+    }
+
+    if 0 < exit_after_ms && ns.sleep(exit_after_ms).await.is_err() {
+        return;
+    }
+
+    // the tidying up is left to the exit, once the script gets killed
+    if let Some(kind) = screensaver {
+        let _ = screensaver::run(ns, kind, options.char_size).await;
+        return;
+    }
+
+    if close_tail_on_end {
+        ui::close_tail(ns);
+    }
+    else if keep_last_frame {
+        shutdown.keep_log();
+    }
+}
+
+/// Writes the frame as it's shown, overlays and all, to `snapshot`, or to
+/// `movie.snapshot.123.txt` for frame 123 of `movie.txt`.
+fn save_snapshot(
+    ns: &NS,
+    filename: &str,
+    snapshot: Option<String>,
+    frame_index: usize,
+    frame: &str,
+) {
+    if frame.is_empty() {
+        log::warn!("there's no frame to take a snapshot of yet");
+        return;
+    }
+
+    // scripts can only write text files
+    let snapshot = match snapshot {
+        Some(s) if s.ends_with(".txt") => s,
+        Some(s) => format!("{}.txt", s),
+        None => {
+            let stem = filename.strip_suffix(".txt").unwrap_or(filename);
+            format!("{}.snapshot.{}.txt", stem, frame_index)
+        },
+    };
+
+    ns.write(&snapshot, frame, "w");
+    log::info!("saved a snapshot to {}", snapshot);
+}
+
+/// Counts down to the instant, in milliseconds since the epoch, behind a
+/// splash in the tail window as sized for the frames.
+async fn wait_until(
+    ns: &NS,
+    at: f64,
+) -> Result<(), Killed> {
+    loop {
+        let left = at - WallClock.now();
+        if left <= 0. {
+            break;
+        }
+
+        ns.clearLog();
+        ns.print(&format!("starting in {}", format_time(left / 1000. + 1.)));
+
+        // down to the second, and then the rest of it
+        ns.sleep(left % 1000.).await?;
+    }
+
+    Ok(())
+}
+
+/// How many frames get decoded ahead while waiting for the next to be due.
+const READ_AHEAD: usize = 4;
+
+/// How many frames get decoded between redraws of the buffering splash.
+const PREROLL_BATCH: usize = 10;
+
+/// Decodes `count` frames ahead, or as many as there are, behind a splash
+/// in the tail window as sized for the frames.
+async fn preroll(
+    ns: &NS,
+    frames: &mut movie::Frames<MovieReader<'_>>,
+    count: usize,
+) -> Result<(), Killed> {
+    let mut read = 0;
+
+    while read < count {
+        ns.clearLog();
+        ns.print(&format!("buffering\u{2026} {}%", read * 100 / count));
+
+        // the splash only goes up once the page gets a turn
+        ns.sleep(0).await?;
+
+        let batch = PREROLL_BATCH.min(count - read);
+        let Ok(decoded) = frames.read_ahead(batch)
+        else {
+            // the frame that won't decode gets reported as it's read
+            break;
+        };
+        read += decoded;
+
+        if decoded < batch {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// How long skipping frames goes on for before the page gets a turn, in
+/// milliseconds, and how many frames get skipped between checks on it.
+const SKIP_SLICE_MS: f64 = 50.;
+const SKIP_BATCH: usize = 10;
+
+/// Skips up to `count` frames, returning how many there were, in slices of
+/// time with the page getting a turn in between, so that seeking far into a
+/// movie doesn't freeze the game until the seek lands.
+async fn skip_frames(
+    ns: &NS,
+    frames: &mut movie::Frames<MovieReader<'_>>,
+    count: usize,
+    stats: &RefCell<Stats>,
+) -> Result<std::io::Result<usize>, Killed> {
+    let mut skipped = 0;
+    let mut slice_started = performance_now();
+
+    while skipped < count {
+        let batch = SKIP_BATCH.min(count - skipped);
+        let batch_skipped =
+            stats.borrow_mut().time_decode(|| frames.skip(batch));
+        let batch_skipped = match batch_skipped {
+            Ok(s) => s,
+            Err(e) => return Ok(Err(e)),
         };
-        buffer += &line;
-        buffer += "\n";
-        line_count += 1;
+        skipped += batch_skipped;
+
+        if batch_skipped < batch {
+            break;
+        }
+
+        if skipped < count
+            && SKIP_SLICE_MS <= performance_now() - slice_started
+        {
+            ns.sleep(0).await?;
+            slice_started = performance_now();
+        }
+    }
+
+    Ok(Ok(skipped))
+}
 
-        if line_count >= y {
-            // sleep
-            if let Some(first_print) = first_print.as_ref() {
-                let next_time = &*first_print + frame_count as f64 / (framerate / 1000.);
-                let now = js_sys::Date::now();
+/// Plays the movie or radio file through to its end.
+async fn play_file(
+    ns: &NS,
+    filename: &str,
+    options: &Options,
+    shutdown: &Shutdown,
+) -> Result<(), Error> {
+    // open a file, or the manifest of the files that the movie is split over
+    let Some(file_contents) = Contents::read(ns, filename)
+    else {
+        return Err(Error::Missing(filename.to_owned()));
+    };
 
-                ns.sleep((next_time - now).round() as u32).await;
+    let unreadable = |e| Error::unreadable(filename, e);
+
+    let mut decoder = open_movie(ns, &file_contents).map_err(unreadable)?;
+    let header = movie::read_header(&mut decoder).map_err(unreadable)?;
+    let mut chunks =
+        read_chunks(&file_contents, &mut decoder).map_err(unreadable)?;
+
+    let mut audio = audio::soundtrack(&chunks);
+
+    if let Some(audio) = audio.as_mut() {
+        audio.set_volume(options.volume);
+        audio.set_muted(options.muted);
+    }
+
+    shutdown.set_audio(audio.as_ref().map(|a| a.stopper()));
+
+    // the audio element has its own copy of the soundtrack, which would
+    // otherwise stay in memory next to it for the whole of the playback
+    chunks.retain(|c| c.tag != movie::AUDIO_TAG);
+
+    let metadata = movie::find_chunk(&chunks, movie::METADATA_TAG)
+        .and_then(|c| Metadata::from_chunk(&c.payload));
+
+    // the tail goes by the movie rather than the path of the script, and
+    // with several players up, their tail windows need telling apart
+    let title = options
+        .title
+        .as_deref()
+        .or_else(|| metadata.as_ref().and_then(|m| m.title.as_deref()))
+        .unwrap_or(filename);
+    let title = match options.instance.as_deref() {
+        Some(instance) => format!("{} ({})", title, instance),
+        None => title.to_owned(),
+    };
+    ui::set_tail_title(ns, &title);
+    // what the title was last set to, which only changes by the second
+    let mut shown_title = String::new();
+    let attribution = metadata
+        .as_ref()
+        .filter(|_| options.attribution)
+        .and_then(|m| m.attribution.as_ref());
+
+    if header.is_radio() {
+        // there are no frames to put it over
+        if let Some(attribution) = attribution {
+            ns.tprint(attribution);
+        }
+
+        let mut playback = Playback::new(audio);
+        playback.port = options.port;
+        playback.now_playing_port = options.now_playing_port;
+        playback.set_speed(options.speed);
+        playback.looping = options.looping;
+        playback.paused = options.paused;
+        playback.seek = options.start.map(control::Seek::To);
+
+        radio::play_radio(ns, filename, playback, options.char_size).await?;
+        shutdown.set_audio(None);
+        return Ok(());
+    }
+
+    let movie::Header {
+        framerate,
+        width: x,
+        height: y,
+        frames: frame_total,
+    } = header;
+
+    let mut playback = Playback::new(audio);
+    playback.port = options.port;
+    playback.now_playing_port = options.now_playing_port;
+    playback.set_speed(options.speed);
+    playback.looping = options.looping;
+    playback.manual = options.manual;
+    playback.paused = options.paused || options.manual;
+    playback.status_shown = options.status;
+    playback.subtitles_shown = options.language.is_some();
+    playback.language = options.language.clone();
+
+    let subtitles = movie::find_chunks(&chunks, movie::SUBTITLES_TAG)
+        .filter_map(|c| Subtitles::from_chunk(&c.payload))
+        .collect::<Vec<_>>();
+
+    let chapters = movie::find_chunk(&chunks, movie::CHAPTERS_TAG)
+        .and_then(|c| Chapters::from_chunk(&c.payload));
+    let lyrics = movie::find_chunk(&chunks, movie::LYRICS_TAG)
+        .and_then(|c| Lyrics::from_chunk(&c.payload));
+
+    if attribution.is_some() {
+        playback.show(Panel::Attribution);
+    }
+
+    let mut frames = movie::Frames::new(decoder, x, y);
+    if let Err(e) = frames.check_first() {
+        shutdown.set_audio(None);
+        return Err(unreadable(e));
+    }
+
+    // the buffers only ever get cleared, so they grow to the largest frame
+    // and stay that way, starting out at the size of a frame without colours
+    let frame_capacity = (x as usize + 1) * y as usize;
+
+    // how large the frames go up, zoomed in
+    let row_zoom = match options.zoom_rows {
+        true => options.zoom,
+        false => 1,
+    };
+    let shown_x = x * options.zoom;
+    let shown_y = y * row_zoom;
+    let mut buffer = String::with_capacity(frame_capacity);
+    // the frame before, which goes up again in place of a corrupt one
+    let mut last_frame = String::with_capacity(frame_capacity);
+
+    let mut frame_count: usize = 0;
+
+    let instance = options.instance.as_deref();
+    let resumed = resume::load(ns, filename, instance);
+    // a movie that got cut short picks up from there rather than --start
+    playback.seek = match resumed.filter(|_| options.resume) {
+        Some(frame) => Some(control::Seek::To(frame as f64 / framerate)),
+        None => options.start.map(control::Seek::To),
+    };
+
+    // the last frame that went up, along with everything drawn over it, and
+    // the status line after the first `frame_len` bytes of it
+    let mut shown = String::with_capacity(frame_capacity);
+    let mut frame_len = 0;
+
+    let mut last_save = js_sys::Date::now();
+    let mut last_publish = f64::NEG_INFINITY;
+
+    // shared with the tidying up, for the report to go up even if the
+    // script gets killed partway through
+    let stats = Rc::new(RefCell::new(Stats::default()));
+    shutdown.set_report(Some({
+        let filename = filename.to_owned();
+        let stats = stats.clone();
+
+        Box::new(move || {
+            format!("{}: {}", filename, stats.borrow().report(framerate))
+        })
+    }));
+    let mut renderer = render::Renderer::new(options.render);
+    let mut fitter = Fitter::new(options.fit, options.char_size);
+    let adjustment = Adjustment::new(options.brightness, options.contrast);
+    let tint = options.theme_tint.then(|| Tint::from_theme(ns)).flatten();
+    if options.theme_tint && tint.is_none() {
+        log::info!("the theme has no colour to tint with, so no tint");
+    }
+    shutdown.set_screen(renderer.remover());
+    let mut benchmark = options.benchmark.then(Benchmark::default);
+    let mut quality = Quality::new();
+
+    // what the movie and its frames take up, the frame before counting as
+    // large as the one being read
+    let memory = |frames: &movie::Frames<_>, buffer: &String, shown: &String| {
+        file_contents.held()
+            + frames.buffered()
+            + 2 * buffer.capacity()
+            + shown.capacity()
+    };
+
+    // how many more frames can be decoded ahead without going over
+    // --max-memory, going by the largest that a frame has been
+    let ahead_room = |frames: &movie::Frames<_>, buffer: &_, shown: &_| {
+        match options.max_memory {
+            Some(max) => {
+                let used = memory(frames, buffer, shown);
+                max.saturating_sub(used) / buffer.capacity().max(1)
+            },
+            None => usize::MAX,
+        }
+    };
+
+    // how long a frame stays up at the speed of the playback
+    let playback_frame_ms = |p: &Playback| 1000. / (framerate * p.speed);
+    // when each frame is due, from when the first one was
+    let mut schedule =
+        Schedule::new(MonotonicClock, playback_frame_ms(&playback));
+
+    // the instant that the movie keeps being lined up with, until it is
+    playback.sync_at = options.sync_at;
+    let mut syncing: Option<f64> = None;
+
+    // the tail window gets sized once, with room for the status line, which
+    // every frame is padded to the height of whether it's shown or not, as
+    // resizing it for every frame has it twitch
+    let (tail_width, tail_height) =
+        options.char_size.tail_size(shown_x, shown_y + 1);
+    ui::resize_tail(ns, tail_width, tail_height);
+    let blank_line = " ".repeat(shown_x as usize) + "\n";
+
+    // the frame that was decoded to go up once the sleep is over, with when
+    // it was due, unless it's the one that started the timeline
+    let mut shown_next: Option<Option<f64>> = None;
+    // how many frames got dropped to get to it
+    let mut dropped = 0;
+
+    // the port is left to the playback, which reads it by itself
+    let ended = event_loop::run_loop(ns, None, async |_| {
+        if let Some(due) = shown_next.take() {
+            if let Some(next_time) = due {
+                let lateness = schedule.now() - next_time;
+                let mut stats = stats.borrow_mut();
+                stats.max_lateness_ms = stats.max_lateness_ms.max(lateness);
+            }
+
+            let format_started = performance_now();
+            let time_ms = frame_count as f64 * 1000. / framerate;
+            let sanitized = sanitize::sanitize(&buffer, x);
+            let mut frame = zoom::zoom(&sanitized, options.zoom, row_zoom);
+
+            if let Some(adjustment) = adjustment.as_ref() {
+                frame = adjustment.apply(&frame).into();
+            }
+
+            if let Some(tint) = tint.as_ref() {
+                frame = tint.apply(&frame).into();
             }
 
-            else {
-                first_print = Some(js_sys::Date::now());
+            // without a language picked, there's only the first track to show
+            let track = match playback.language.as_ref() {
+                Some(l) => subtitles.iter().find(|s| s.language == *l),
+                None => subtitles.first(),
+            };
+            let cue = track
+                .filter(|_| playback.subtitles_shown)
+                .and_then(|s| s.active(time_ms));
+
+            if let Some(cue) = cue {
+                let style = &options.subs_style;
+                frame = subtitles::overlay(&frame, cue, shown_x, style).into();
+            }
+
+            // the lyrics keep out of the way of the subtitles
+            if let Some(lyrics) =
+                lyrics.as_ref().filter(|l| l.is_active(time_ms))
+            {
+                let position = match cue {
+                    Some(_) => options.subs_style.position.opposite(),
+                    None => options.subs_style.position,
+                };
+
+                frame =
+                    lyrics.overlay(&frame, time_ms, shown_x, position).into();
+            }
+
+            match (playback.panel(), chapters.as_ref(), metadata.as_ref()) {
+                (Some(Panel::Chapters), Some(chapters), _) => {
+                    frame = chapters.overlay(&frame, shown_x, time_ms).into();
+                },
+                (Some(Panel::Info), _, Some(metadata)) => {
+                    frame = metadata.overlay(&frame, shown_x).into();
+                },
+                (Some(Panel::Attribution), _, Some(metadata)) => {
+                    frame =
+                        metadata.attribution_overlay(&frame, shown_x).into();
+                },
+                _ => {},
+            }
+
+            shown.clear();
+            shown += &frame;
+            sanitize::end_with_reset(&mut shown);
+            frame_len = shown.len();
+
+            match playback.status_shown {
+                true => {
+                    let length = frame_total.map(|f| f as f64 / framerate);
+                    let paused = playback.paused;
+                    let position = time_ms / 1000.;
+                    let stride = quality.stride();
+                    shown += &status::status_line(
+                        position, length, paused, stride, shown_x,
+                    );
+                },
+                false => shown += &blank_line,
+            }
+
+            let print_started = performance_now();
+            if let Some(benchmark) = benchmark.as_mut() {
+                benchmark.record(Step::Format, print_started - format_started);
             }
 
             // print
-            ns.clearLog();
-            ns.print(&buffer);
-            ns.resizeTail(x * 10, y * 30 + 1);
-            ns.resizeTail(x * 10, y * 30);
+            let drawn = osd::draw(&shown, playback.osd(), shown_x);
+            let fitted = fitter.fit(ns, shown_x, shown_y + 1, &drawn);
+            renderer.print(ns, &fitted);
+
+            let length = frame_total.map(|f| f as f64 / framerate);
+            let frame_title =
+                status::title(&title, time_ms / 1000., length, playback.paused);
+            if frame_title != shown_title {
+                ui::set_tail_title(ns, &frame_title);
+                shown_title = frame_title;
+            }
+
+            if let Some(benchmark) = benchmark.as_mut() {
+                benchmark
+                    .record(Step::Print, performance_now() - print_started);
+            }
+
+            std::mem::swap(&mut buffer, &mut last_frame);
             buffer.clear();
 
-            //buffer += "\u{001b}[0m\n";
+            log::debug!("frame {}", frame_count);
+
+            let memory = memory(&frames, &buffer, &shown);
+            stats.borrow_mut().record_memory(memory);
+
+            if resume::SAVE_INTERVAL_MS <= js_sys::Date::now() - last_save {
+                resume::save(ns, filename, instance, frame_count);
+                last_save = js_sys::Date::now();
+            }
+
+            let since_publish = js_sys::Date::now() - last_publish;
+            if now_playing::PUBLISH_INTERVAL_MS <= since_publish {
+                now_playing::publish(
+                    ns,
+                    filename,
+                    Some(frame_count),
+                    &playback,
+                );
+                last_publish = js_sys::Date::now();
+            }
+
+            if !playback.paused {
+                quality.record(dropped);
+            }
 
-            ns.tprint(&format!("frame {}", frame_count));
             frame_count += 1;
-            line_count = 0;
+            stats.borrow_mut().shown += 1;
+        }
+
+        panic::set_frame(Some(frame_count));
+        playback.position = frame_count as f64 / framerate;
+        playback.poll(ns);
+
+        // nothing gets printed or put up while paused, so the status line
+        // and the state have to say so before the wait
+        if playback.paused && schedule.is_started() {
+            let shown_frame = frame_count.saturating_sub(1);
+            now_playing::publish(ns, filename, Some(shown_frame), &playback);
+            last_publish = js_sys::Date::now();
+
+            let position = shown_frame as f64 / framerate;
+            let length = frame_total.map(|f| f as f64 / framerate);
+            let paused_title = status::title(&title, position, length, true);
+            if paused_title != shown_title {
+                ui::set_tail_title(ns, &paused_title);
+                shown_title = paused_title;
+            }
+
+            // the message of the pause stays up for as long as it lasts
+            let message = playback.osd();
+            if playback.status_shown || message.is_some() {
+                shown.truncate(frame_len);
+
+                match playback.status_shown {
+                    true => {
+                        let position = shown_frame as f64 / framerate;
+                        let length = frame_total.map(|f| f as f64 / framerate);
+                        let stride = quality.stride();
+                        shown += &status::status_line(
+                            position, length, true, stride, shown_x,
+                        );
+                    },
+                    false => shown += &blank_line,
+                }
+
+                let drawn = osd::draw(&shown, message, shown_x);
+                let fitted = fitter.fit(ns, shown_x, shown_y + 1, &drawn);
+                renderer.print(ns, &fitted);
+            }
+        }
+
+        // the frames have to wait out the pause as well, though the first one
+        // goes up regardless, so that starting paused shows something
+        let paused_for = match schedule.is_started() {
+            true => {
+                playback
+                    .wait_while_paused(ns, |_| frame_count as f64 / framerate)
+                    .await?
+            },
+            false => 0.,
+        };
+
+        stats.borrow_mut().paused_ms += paused_for;
+
+        schedule.delay(paused_for);
+
+        // a change of speed keeps the next frame due when it was
+        schedule.set_frame_ms(frame_count, playback_frame_ms(&playback));
+
+        if let Some(snapshot) = playback.snapshot.take() {
+            let shown_frame = frame_count.saturating_sub(1);
+            let frame = &shown[.. frame_len];
+            save_snapshot(ns, filename, snapshot, shown_frame, frame);
+
+            // the wait got cut short for it
+            if playback.paused {
+                return Ok(Next::Now);
+            }
+        }
+
+        playback.repeat_if_due(false);
+
+        // a sync starts the movie over at the instant, or has it seek to
+        // where it'd be by now
+        if let Some(at) = playback.sync_at.take() {
+            if WallClock.now() < at {
+                if let Some(audio) = playback.audio.as_mut() {
+                    audio.pause();
+                }
+
+                wait_until(ns, at).await?;
+            }
+
+            syncing = Some(at);
+        }
+
+        // seeking takes a while, which the playback has to make up for with
+        // another seek, until it lands close enough
+        if let Some(at) = syncing.filter(|_| !playback.paused) {
+            let since = (WallClock.now() - at) / 1000. * playback.speed;
+            let position = frame_count as f64 / framerate;
+
+            match (since - position).abs() * framerate < 1. {
+                true => syncing = None,
+                false => playback.seek = Some(control::Seek::To(since)),
+            }
+        }
+        else {
+            syncing = None;
         }
+
+        if playback.is_seeking() {
+            // steps count from the frame on screen, which is the one before
+            // the next
+            let target_frame = match playback.seek.take() {
+                Some(seek) => {
+                    let target = seek.target(frame_count as f64 / framerate);
+                    (target * framerate).round() as i64
+                },
+                None => frame_count as i64 - 1,
+            };
+            let steps = std::mem::take(&mut playback.steps);
+            let target_frame = (target_frame + steps).max(0) as usize;
+
+            // there's no going back in the stream, so it starts over
+            if target_frame < frame_count {
+                frames = match open_frames(ns, &file_contents, x, y) {
+                    Ok(f) => f,
+                    Err(e) => {
+                        return Ok(Next::Done(Err(e)));
+                    },
+                };
+                frame_count = 0;
+            }
+
+            let skip = target_frame - frame_count;
+            match skip_frames(ns, &mut frames, skip, &stats).await? {
+                Ok(skipped) => frame_count += skipped,
+                Err(e) => {
+                    return Ok(Next::Done(Err(e)));
+                },
+            }
+
+            // the clock carries on from the frame that the seek landed on
+            schedule.restart(frame_count);
+
+            // audio that already ended has to be played again
+            let position = frame_count as f64 / framerate;
+            if schedule.is_started() {
+                playback.notify(format!("\u{279c} {}", format_time(position)));
+            }
+            if let Some(audio) = playback.audio.as_mut() {
+                audio.seek(position);
+
+                if schedule.is_started() && !playback.paused {
+                    audio.resume(position);
+                }
+            }
+        }
+
+        // when printing falls behind, the frames that are already late get
+        // dropped rather than shown late
+        dropped = 0;
+        if benchmark.is_none() {
+            let late = schedule.late(frame_count);
+
+            if 0 < late {
+                let skipped =
+                    stats.borrow_mut().time_decode(|| frames.skip(late));
+                match skipped {
+                    Ok(skipped) => {
+                        frame_count += skipped;
+                        stats.borrow_mut().dropped += skipped;
+                        dropped = skipped;
+                    },
+                    Err(e) => {
+                        return Ok(Next::Done(Err(e)));
+                    },
+                }
+            }
+        }
+
+        // under load, only every so many frames go up, which keeps the ones
+        // that do evenly apart
+        let stride = quality.stride();
+        if 1 < stride
+            && schedule.is_started()
+            && !playback.paused
+            && benchmark.is_none()
+        {
+            let skip = (stride - frame_count % stride) % stride;
+
+            let skipped = stats.borrow_mut().time_decode(|| frames.skip(skip));
+            match skipped {
+                Ok(skipped) => frame_count += skipped,
+                Err(e) => {
+                    return Ok(Next::Done(Err(e)));
+                },
+            }
+        }
+
+        // the clock starts with frames to go on with, wherever it starts
+        if !schedule.is_started() && 0 < options.preroll {
+            let room = ahead_room(&frames, &buffer, &shown);
+            if room < options.preroll {
+                log::info!(
+                    "only prerolling {} frames to keep under --max-memory",
+                    room,
+                );
+            }
+
+            let count = options.preroll.min(room);
+            preroll(ns, &mut frames, count).await?;
+        }
+
+        let decode_started = performance_now();
+        let decoded =
+            stats.borrow_mut().time_decode(|| frames.read_frame(&mut buffer));
+        match decoded {
+            Ok(Decoded::Whole) => {
+                if let Some(benchmark) = benchmark.as_mut() {
+                    let decode_ms = performance_now() - decode_started;
+                    benchmark.record(Step::Decode, decode_ms);
+                }
+            },
+            // the frame before stays up for the time of the corrupt one
+            Ok(Decoded::Corrupt) => {
+                stats.borrow_mut().corrupt.push(frame_count);
+                buffer += &last_frame;
+            },
+            Ok(Decoded::End) => {
+                if let Err(e) = header.check_count(frame_count) {
+                    return Ok(Next::Done(Err(e.into())));
+                }
+
+                playback.position = frame_count as f64 / framerate;
+
+                // the seek back happens at the top of the loop
+                if 0 < frame_count && playback.repeat_if_due(true) {
+                    return Ok(Next::Now);
+                }
+
+                // stepping past the last slide stays on it
+                if 0 < frame_count && playback.manual {
+                    let last = (frame_count - 1) as f64 / framerate;
+                    playback.seek = Some(control::Seek::To(last));
+                    return Ok(Next::Now);
+                }
+
+                return Ok(Next::Done(Ok(())));
+            },
+            Err(e) => {
+                return Ok(Next::Done(Err(e)));
+            },
+        }
+
+        // every frame is due at a point of the timeline, so the time that a
+        // sleep overshoots by doesn't add up over the frames
+        let Some(next_time) = schedule.due(frame_count)
+        else {
+            schedule.start(frame_count);
+            stats.borrow_mut().started = Some(performance_now());
+
+            if let Some(audio) = playback.audio.as_mut() {
+                audio.play();
+
+                if playback.paused {
+                    audio.pause();
+                }
+            }
+
+            // the first frame goes up as soon as the timeline starts
+            shown_next = Some(None);
+            return Ok(Next::Now);
+        };
+
+        // the time left before the frame is due goes to decoding the ones
+        // after it, for as long as another one would fit in it
+        let mut decode_ms = 0.;
+        while benchmark.is_none()
+            && frames.ahead() < READ_AHEAD
+            && 2. * decode_ms < next_time - schedule.now()
+            && 0 < ahead_room(&frames, &buffer, &shown)
+        {
+            let decode_started = performance_now();
+            let decoded =
+                stats.borrow_mut().time_decode(|| frames.read_ahead(1));
+            decode_ms = performance_now() - decode_started;
+
+            // the frame that won't decode gets reported as it's read
+            if !matches!(decoded, Ok(1)) {
+                break;
+            }
+        }
+
+        let wait = match benchmark {
+            Some(_) => 0.,
+            None => next_time - schedule.now(),
+        };
+
+        // late or not, the page needs the sleep to get a turn
+        shown_next = Some(Some(next_time));
+        Ok(Next::Wait(wait))
+    })
+    .await?;
+
+    log::info!("{}: {}", filename, stats.borrow().report(framerate));
+    shutdown.set_report(None);
+    if let Some(benchmark) = benchmark {
+        log::info!("{}", benchmark.report());
+    }
+
+    // what the movie stopped short over, once the tidying up is done
+    let failure = match ended {
+        Ok(()) => {
+            resume::clear(ns, filename, instance);
+            None
+        },
+        Err(e) => Some(e),
+    };
+
+    if let Some(audio) = playback.audio.as_mut() {
+        audio.stop();
+    }
+    shutdown.set_audio(None);
+
+    match failure {
+        Some(e) => Err(Error::unreadable(filename, e)),
+        None => Ok(()),
     }
 }
@@ -0,0 +1,94 @@
+//! A logger for the `log` crate, which puts the messages up in the terminal
+//! or in the log of the script, each with the time and how much it matters,
+//! so that any script of the crate logs with `log::info!` and the like once
+//! it has called [`install`], from within [`script::run`].
+//!
+//! Errors and warnings always go up in the terminal, for them not to get
+//! lost under the frames in the log, and the rest go wherever the script
+//! asked for. Only what's at least [`log::max_level`] goes up at all, which
+//! [`install`] starts out at `Info`.
+//!
+//! The messages go up for the script that's running when they're logged,
+//! and whatever gets logged once it's been killed is let go of.
+
+use log::{
+    Level,
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+};
+
+use crate::script;
+
+/// Where the messages other than the errors and the warnings go up.
+#[derive(Clone, Copy, Default)]
+pub enum Sink {
+    #[default]
+    Terminal,
+    /// The log of the script, which is also what the tail window shows.
+    Log,
+}
+
+struct Logger;
+
+static LOGGER: Logger = Logger;
+
+/// The time of day, as `12:34:56`.
+fn timestamp() -> String {
+    let now = js_sys::Date::new_0();
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        now.get_hours(),
+        now.get_minutes(),
+        now.get_seconds(),
+    )
+}
+
+impl Log for Logger {
+    fn enabled(
+        &self,
+        metadata: &Metadata<'_>,
+    ) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(
+        &self,
+        record: &Record<'_>,
+    ) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // the game colours the lines of the terminal by the level in them
+        let line =
+            format!("{} {} {}", timestamp(), record.level(), record.args());
+        script::with(|script| {
+            let sink = match record.level() {
+                Level::Error | Level::Warn => Sink::Terminal,
+                _ => script.sink.get(),
+            };
+
+            // the game throws at the printing once the script is killed,
+            // which there's nothing to do about but leave the line out
+            let _ = match sink {
+                Sink::Terminal => script.ns.tprint(&line),
+                Sink::Log => script.ns.print(&line),
+            };
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Has the messages that the running script logs from here on go up for it,
+/// the ones other than errors and warnings going to `sink`.
+pub fn install(sink: Sink) {
+    script::with(|script| script.sink.set(sink));
+
+    // the logger only gets set the once, for whichever script is running
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Info);
+}
@@ -0,0 +1,167 @@
+//! Karaoke: the line of lyrics being sung, drawn over the frames with the
+//! words highlighted as they come.
+
+use crate::subtitles::{
+    self,
+    Position,
+};
+
+struct Word {
+    start_ms: f64,
+    text: String,
+}
+
+struct Line {
+    start_ms: f64,
+    end_ms: f64,
+    words: Vec<Word>,
+}
+
+pub struct Lyrics {
+    /// Sorted by start time.
+    lines: Vec<Line>,
+}
+
+/// The SGR codes of the words that were sung, the word being sung, and the
+/// words yet to be sung.
+const SUNG: &str = "0;93;40";
+const SINGING: &str = "0;1;30;103";
+const UPCOMING: &str = "0;97;40";
+
+impl Lyrics {
+    /// Reads the lines out of the payload of a lyrics chunk, which has cues
+    /// like subtitles do, with a `<start> <word>` line per word.
+    pub fn from_chunk(payload: &[u8]) -> Option<Lyrics> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let mut lines = vec![];
+
+        for cue in subtitles::parse_cues(payload)? {
+            let words = cue
+                .lines
+                .iter()
+                .map(|line| {
+                    let (start, text) = line.split_once(' ')?;
+
+                    Some(Word {
+                        start_ms: start.parse().ok()?,
+                        text: text.to_owned(),
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            lines.push(Line {
+                start_ms: cue.start_ms,
+                end_ms: cue.end_ms,
+                words,
+            });
+        }
+
+        Some(Lyrics {
+            lines,
+        })
+    }
+
+    /// Whether there's a line being sung at `time_ms` into the movie.
+    pub fn is_active(
+        &self,
+        time_ms: f64,
+    ) -> bool {
+        self.active(time_ms).is_some()
+    }
+
+    fn active(
+        &self,
+        time_ms: f64,
+    ) -> Option<&Line> {
+        let started = self.lines.partition_point(|l| l.start_ms <= time_ms);
+        self.lines[.. started]
+            .iter()
+            .rev()
+            .find(|l| time_ms < l.end_ms)
+    }
+
+    /// Replaces the top or bottom rows of the frame with the line being sung
+    /// at `time_ms`, wrapped to its width and centered on a black background.
+    pub fn overlay(
+        &self,
+        frame: &str,
+        time_ms: f64,
+        width: u32,
+        position: Position,
+    ) -> String {
+        let Some(line) = self.active(time_ms)
+        else {
+            return frame.to_owned();
+        };
+
+        let width = width as usize;
+
+        // the word being sung is the last one that has started
+        let singing = line
+            .words
+            .partition_point(|w| w.start_ms <= time_ms)
+            .checked_sub(1);
+
+        // lay the words out into rows of whole words, as their indices
+        let mut layout: Vec<(Vec<usize>, usize)> = vec![];
+        for (index, word) in line.words.iter().enumerate() {
+            let word_width = word.text.chars().count();
+
+            match layout.last_mut() {
+                Some((words, row_width))
+                    if *row_width + 1 + word_width <= width =>
+                {
+                    words.push(index);
+                    *row_width += 1 + word_width;
+                },
+                _ => layout.push((vec![index], word_width)),
+            }
+        }
+
+        let rows = frame.lines().collect::<Vec<_>>();
+        let first_text_row = match position {
+            Position::Top => 0,
+            Position::Bottom => rows.len().saturating_sub(layout.len()),
+        };
+
+        let mut output = String::with_capacity(frame.len());
+
+        for (index, row) in rows.iter().enumerate() {
+            let Some((words, row_width)) = index
+                .checked_sub(first_text_row)
+                .and_then(|text_row| layout.get(text_row))
+            else {
+                output += row;
+                output += "\n";
+                continue;
+            };
+
+            let padding = width.saturating_sub(*row_width);
+            let left = padding / 2;
+
+            output += &format!("\u{1b}[{}m", UPCOMING);
+            output.extend(std::iter::repeat_n(' ', left));
+
+            for (nth, &word) in words.iter().enumerate() {
+                if nth != 0 {
+                    output += &format!("\u{1b}[{}m ", UPCOMING);
+                }
+
+                let style = match singing {
+                    Some(singing) if word < singing => SUNG,
+                    Some(singing) if word == singing => SINGING,
+                    _ => UPCOMING,
+                };
+
+                output += &format!("\u{1b}[{}m", style);
+                output += &line.words[word].text;
+            }
+
+            output += &format!("\u{1b}[{}m", UPCOMING);
+            output.extend(std::iter::repeat_n(' ', padding - left));
+            output += "\u{1b}[0m\n";
+        }
+
+        output
+    }
+}
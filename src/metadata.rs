@@ -0,0 +1,120 @@
+//! The info panel: the credits of the movie, drawn over the top rows of the
+//! frames.
+
+use crate::subtitles;
+
+#[derive(Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub source_url: Option<String>,
+    pub attribution: Option<String>,
+}
+
+impl Metadata {
+    /// Reads the fields out of the payload of a metadata chunk. Unknown
+    /// fields are skipped, so that newer encoders can add more.
+    pub fn from_chunk(payload: &[u8]) -> Option<Metadata> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let mut metadata = Metadata::default();
+
+        for line in payload.lines() {
+            let Some((key, value)) = line.split_once('=')
+            else {
+                continue;
+            };
+
+            let field = match key {
+                "title" => &mut metadata.title,
+                "author" => &mut metadata.author,
+                "source" => &mut metadata.source_url,
+                "attribution" => &mut metadata.attribution,
+                _ => continue,
+            };
+
+            *field = Some(value.to_owned());
+        }
+
+        Some(metadata)
+    }
+
+    /// The lines of the info panel, each with its SGR codes.
+    fn lines(&self) -> Vec<(&'static str, String)> {
+        let mut lines = vec![];
+
+        if let Some(title) = self.title.as_ref() {
+            lines.push(("1;97", title.clone()));
+        }
+
+        if let Some(author) = self.author.as_ref() {
+            lines.push(("97", format!("by {}", author)));
+        }
+
+        if let Some(source_url) = self.source_url.as_ref() {
+            lines.push(("36", source_url.clone()));
+        }
+
+        if let Some(attribution) = self.attribution.as_ref() {
+            lines.push(("37", attribution.clone()));
+        }
+
+        lines
+    }
+
+    /// Replaces the top rows of the frame with the credits.
+    pub fn overlay(
+        &self,
+        frame: &str,
+        width: u32,
+    ) -> String {
+        draw_panel(frame, width, self.lines())
+    }
+
+    /// Replaces the top rows of the frame with the attribution alone.
+    pub fn attribution_overlay(
+        &self,
+        frame: &str,
+        width: u32,
+    ) -> String {
+        let lines = self.attribution.iter().map(|a| ("37", a.clone()));
+        draw_panel(frame, width, lines.collect())
+    }
+}
+
+/// Replaces the top rows of the frame with the lines, wrapped to its width on
+/// a black background.
+fn draw_panel(
+    frame: &str,
+    width: u32,
+    lines: Vec<(&str, String)>,
+) -> String {
+    let width = width as usize;
+
+    // a character of margin on the left
+    let mut panel = vec![];
+    for (style, line) in lines {
+        for line in subtitles::wrap(&[line], width.saturating_sub(1)) {
+            panel.push((style, line));
+        }
+    }
+
+    let mut output = String::with_capacity(frame.len());
+
+    for (index, row) in frame.lines().enumerate() {
+        let Some((style, text)) = panel.get(index)
+        else {
+            output += row;
+            output += "\n";
+            continue;
+        };
+
+        let padding = width.saturating_sub(text.chars().count() + 1);
+
+        output += &format!("\u{1b}[0;{};40m ", style);
+        output += text;
+        output.extend(std::iter::repeat_n(' ', padding));
+        output += "\u{1b}[0m\n";
+    }
+
+    output
+}
@@ -0,0 +1,47 @@
+//! What the player is playing, put up on a port for other scripts to show
+//! or to build controls on, e.g. `JSON.parse(ns.peek(22))`. Players started
+//! with `--instance` put it up on a port of their own.
+//!
+//! The port only ever holds the latest state, as a JSON object with the
+//! `file`, the `frame` (`null` for radio files), the `position` in seconds,
+//! whether it's `paused`, and the `speed`.
+
+use serde::Serialize;
+
+use crate::{
+    ns::ports::Port,
+    playback::Playback,
+    NS,
+};
+
+pub const NOW_PLAYING_PORT: u32 = 22;
+
+/// How often the state gets put up, in milliseconds.
+pub const PUBLISH_INTERVAL_MS: f64 = 1000.;
+
+#[derive(Serialize)]
+struct State<'a> {
+    file: &'a str,
+    frame: Option<usize>,
+    position: f64,
+    paused: bool,
+    speed: f64,
+}
+
+/// Replaces the state on the port with that of the playback.
+pub fn publish(
+    ns: &NS,
+    filename: &str,
+    frame: Option<usize>,
+    playback: &Playback,
+) {
+    let port = Port::new(ns, playback.now_playing_port);
+    port.clear();
+    let _ = port.write(&State {
+        file: filename,
+        frame,
+        position: playback.position,
+        paused: playback.paused,
+        speed: playback.speed,
+    });
+}
@@ -0,0 +1,94 @@
+//! The arguments that a script was run with, which the game hands over as
+//! strings, numbers, or booleans by how they look, so that `run x.js 30`
+//! gets the number 30 rather than `"30"`.
+
+use std::fmt;
+
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+
+use super::NS;
+
+/// One of the arguments of a script.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ScriptArg {
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+impl ScriptArg {
+    /// Reads an argument, as a string if it's none of the three.
+    pub fn from_js(value: &JsValue) -> ScriptArg {
+        if let Some(b) = value.as_bool() {
+            ScriptArg::Bool(b)
+        }
+        else if let Some(n) = value.as_f64() {
+            ScriptArg::Number(n)
+        }
+        else {
+            let text = value.as_string();
+            ScriptArg::String(text.unwrap_or_else(|| format!("{:?}", value)))
+        }
+    }
+
+    /// The argument as it was typed, for a filename that's all digits and
+    /// the like.
+    pub fn text(&self) -> String {
+        self.to_string()
+    }
+
+    /// The argument as a number, if it's one or a string of one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ScriptArg::Number(n) => Some(*n),
+            ScriptArg::String(s) => s.parse().ok(),
+            ScriptArg::Bool(_) => None,
+        }
+    }
+
+    /// The argument as a boolean, if it's one or `true` or `false` as a
+    /// string.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ScriptArg::Bool(b) => Some(*b),
+            ScriptArg::String(s) => s.parse().ok(),
+            ScriptArg::Number(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ScriptArg {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            ScriptArg::Bool(b) => write!(f, "{}", b),
+            ScriptArg::Number(n) => write!(f, "{}", n),
+            ScriptArg::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Back to what the game handed over, for handing the argument on to
+/// another script as it was.
+impl From<&ScriptArg> for JsValue {
+    fn from(arg: &ScriptArg) -> JsValue {
+        match arg {
+            ScriptArg::Bool(b) => JsValue::from_bool(*b),
+            ScriptArg::Number(n) => JsValue::from_f64(*n),
+            ScriptArg::String(s) => JsValue::from_str(s),
+        }
+    }
+}
+
+/// The arguments of the script, in order.
+pub fn args(ns: &NS) -> std::vec::IntoIter<ScriptArg> {
+    ns.args()
+        .iter()
+        .map(|a| ScriptArg::from_js(&a))
+        .collect::<Vec<_>>()
+        .into_iter()
+}
@@ -0,0 +1,136 @@
+//! [`ns_bindings!`], which binds functions of the game a line each.
+
+/// Binds functions of `ns`, a line each, as methods of [`SafeNs`] that hand
+/// back whatever the function throws instead of throwing it on, along with
+/// the call that threw it. Each goes by its name in Rust and then its name in
+/// the game:
+///
+/// ```ignore
+/// ns_bindings! {
+///     /// The server that the script runs on.
+///     pub fn get_hostname = getHostname() -> String;
+///     pub async fn next_port_write = nextPortWrite(port: u32) -> JsValue;
+/// }
+/// ```
+///
+/// The invocation needs a module of its own, as the raw bindings go in a
+/// module named `raw` next to it, on a type of its own for the methods not
+/// to clash with the ones of [`NS`]. It's only for the modules of `ns`
+/// itself, since a crate built on this one couldn't add methods to
+/// [`SafeNs`] anyway.
+///
+/// [`NS`]: crate::NS
+/// [`SafeNs`]: crate::ns::SafeNs
+macro_rules! ns_bindings {
+    (@raw) => {};
+    (@raw
+        $(#[$meta:meta])*
+        $vis:vis async fn $name:ident = $js_name:ident(
+            $($arg:ident: $arg_type:ty),* $(,)?
+        ) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(method, catch, js_name = $js_name)]
+            pub async fn $name(
+                this: &Ns,
+                $($arg: $arg_type),*
+            ) -> Result<($($ret)?), ::wasm_bindgen::JsValue>;
+        }
+
+        $crate::ns::bindings::ns_bindings!(@raw $($rest)*);
+    };
+    (@raw
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident = $js_name:ident(
+            $($arg:ident: $arg_type:ty),* $(,)?
+        ) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(method, catch, js_name = $js_name)]
+            pub fn $name(
+                this: &Ns,
+                $($arg: $arg_type),*
+            ) -> Result<($($ret)?), ::wasm_bindgen::JsValue>;
+        }
+
+        $crate::ns::bindings::ns_bindings!(@raw $($rest)*);
+    };
+
+    (@wrap) => {};
+    (@wrap
+        $(#[$meta:meta])*
+        $vis:vis async fn $name:ident = $js_name:ident(
+            $($arg:ident: $arg_type:ty),* $(,)?
+        ) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        #[allow(unused_parens)]
+        impl $crate::ns::SafeNs {
+            $(#[$meta])*
+            $vis async fn $name(
+                &self,
+                $($arg: $arg_type),*
+            ) -> Result<($($ret)?), $crate::ns::NsError> {
+                use ::wasm_bindgen::JsCast as _;
+
+                let ns = self.ns().unchecked_ref::<raw::Ns>();
+                ns.$name($($arg),*).await.map_err(|e| {
+                    $crate::ns::NsError::from(e)
+                        .during(stringify!($js_name), &[$(&$arg),*])
+                })
+            }
+        }
+
+        $crate::ns::bindings::ns_bindings!(@wrap $($rest)*);
+    };
+    (@wrap
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident = $js_name:ident(
+            $($arg:ident: $arg_type:ty),* $(,)?
+        ) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        #[allow(unused_parens)]
+        impl $crate::ns::SafeNs {
+            $(#[$meta])*
+            $vis fn $name(
+                &self,
+                $($arg: $arg_type),*
+            ) -> Result<($($ret)?), $crate::ns::NsError> {
+                use ::wasm_bindgen::JsCast as _;
+
+                let ns = self.ns().unchecked_ref::<raw::Ns>();
+                ns.$name($($arg),*).map_err(|e| {
+                    $crate::ns::NsError::from(e)
+                        .during(stringify!($js_name), &[$(&$arg),*])
+                })
+            }
+        }
+
+        $crate::ns::bindings::ns_bindings!(@wrap $($rest)*);
+    };
+
+    ($($bindings:tt)*) => {
+        #[allow(non_snake_case, unused_parens)]
+        mod raw {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[::wasm_bindgen::prelude::wasm_bindgen]
+            extern "C" {
+                /// The object of `ns`, which the raw bindings go on.
+                pub type Ns;
+            }
+
+            $crate::ns::bindings::ns_bindings!(@raw $($bindings)*);
+        }
+
+        $crate::ns::bindings::ns_bindings!(@wrap $($bindings)*);
+    };
+}
+
+pub(crate) use ns_bindings;
@@ -0,0 +1,69 @@
+//! The files of the servers, which only ever hold text.
+
+use wasm_bindgen::prelude::*;
+
+use super::NS;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Reads the file off the server that the script runs on, which is
+    /// empty if there's no such file.
+    #[wasm_bindgen(method)]
+    pub fn read(
+        ns: &NS,
+        filename: &str,
+    ) -> String;
+
+    /// Writes to the file, with `mode` as `w` to overwrite it or `a` to
+    /// append to it.
+    #[wasm_bindgen(method)]
+    pub fn write(
+        ns: &NS,
+        filename: &str,
+        data: &str,
+        mode: &str,
+    );
+
+    /// Removes the file, from the server of the script unless `host` is
+    /// given, returning whether it was there.
+    #[wasm_bindgen(method)]
+    pub fn rm(
+        ns: &NS,
+        filename: &str,
+        host: Option<&str>,
+    ) -> bool;
+
+    #[wasm_bindgen(method)]
+    pub fn fileExists(
+        ns: &NS,
+        filename: &str,
+        host: Option<&str>,
+    ) -> bool;
+
+    /// The files on `host`, with `substring` in their names if it's given.
+    #[wasm_bindgen(method)]
+    pub fn ls(
+        ns: &NS,
+        host: &str,
+        substring: Option<&str>,
+    ) -> js_sys::Array;
+
+    /// Moves the file on `host` to `destination`, overwriting whatever was
+    /// there.
+    #[wasm_bindgen(method)]
+    pub fn mv(
+        ns: &NS,
+        host: &str,
+        source: &str,
+        destination: &str,
+    );
+}
+
+super::bindings::ns_bindings! {
+    pub fn read = read(filename: &str) -> String;
+    pub fn write = write(filename: &str, data: &str, mode: &str);
+    pub fn rm = rm(filename: &str, host: Option<&str>) -> bool;
+    pub fn file_exists = fileExists(filename: &str, host: Option<&str>) -> bool;
+    pub fn ls = ls(host: &str, substring: Option<&str>) -> js_sys::Array;
+    pub fn mv = mv(host: &str, source: &str, destination: &str);
+}
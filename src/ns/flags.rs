@@ -0,0 +1,146 @@
+//! Reading the flags that a script was run with through `ns.flags`, straight
+//! into a struct that derives `Deserialize`, as the player reads its own.
+//!
+//! Every flag gets declared with its default, which `ns.flags` also goes by
+//! for the type of the flag, so that `--port 21` reads as a number and
+//! `--verbose` on its own as `true`. A field of the struct goes by the name
+//! of its flag, with the arguments that aren't flags under `_`.
+
+use serde::de::DeserializeOwned;
+use wasm_bindgen::JsValue;
+
+use super::{
+    objects,
+    NsError,
+    NS,
+};
+
+/// What a flag is unless it's given, which also makes it a flag of that type.
+#[derive(Clone, Debug)]
+pub enum FlagDefault {
+    /// A flag that's `true` when given, taking no value.
+    Bool(bool),
+    Number(f64),
+    String(String),
+    /// A flag that can be given more than once, each time with a value.
+    Strings(Vec<String>),
+}
+
+impl From<bool> for FlagDefault {
+    fn from(default: bool) -> FlagDefault {
+        FlagDefault::Bool(default)
+    }
+}
+
+impl From<f64> for FlagDefault {
+    fn from(default: f64) -> FlagDefault {
+        FlagDefault::Number(default)
+    }
+}
+
+impl From<u32> for FlagDefault {
+    fn from(default: u32) -> FlagDefault {
+        FlagDefault::Number(default.into())
+    }
+}
+
+impl From<&str> for FlagDefault {
+    fn from(default: &str) -> FlagDefault {
+        FlagDefault::String(default.to_owned())
+    }
+}
+
+impl From<Vec<String>> for FlagDefault {
+    fn from(default: Vec<String>) -> FlagDefault {
+        FlagDefault::Strings(default)
+    }
+}
+
+impl From<&FlagDefault> for JsValue {
+    fn from(default: &FlagDefault) -> JsValue {
+        match default {
+            FlagDefault::Bool(b) => JsValue::from_bool(*b),
+            FlagDefault::Number(n) => JsValue::from_f64(*n),
+            FlagDefault::String(s) => JsValue::from_str(s),
+            FlagDefault::Strings(strings) => {
+                strings
+                    .iter()
+                    .map(|s| JsValue::from_str(s))
+                    .collect::<js_sys::Array>()
+                    .into()
+            },
+        }
+    }
+}
+
+/// Tells what's wrong with the flags read, if anything.
+type Check<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+
+/// The flags of a script, to be read into a `T`.
+pub struct Flags<T> {
+    flags: Vec<(String, FlagDefault)>,
+    checks: Vec<Check<T>>,
+}
+
+impl<T: DeserializeOwned> Default for Flags<T> {
+    fn default() -> Flags<T> {
+        Flags::new()
+    }
+}
+
+impl<T: DeserializeOwned> Flags<T> {
+    pub fn new() -> Flags<T> {
+        Flags {
+            flags: vec![],
+            checks: vec![],
+        }
+    }
+
+    /// Declares the flag `--name`, which is `default` unless given.
+    pub fn flag(
+        mut self,
+        name: &str,
+        default: impl Into<FlagDefault>,
+    ) -> Flags<T> {
+        self.flags.push((name.to_owned(), default.into()));
+        self
+    }
+
+    /// Has the flags checked by `check` once read, for what their types
+    /// alone can't tell, like a number that's out of range.
+    pub fn check(
+        mut self,
+        check: impl Fn(&T) -> Result<(), String> + 'static,
+    ) -> Flags<T> {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Reads the flags out of the arguments of the script, or tells what's
+    /// wrong with them, for the terminal.
+    pub fn parse(
+        &self,
+        ns: &NS,
+    ) -> Result<T, String> {
+        let schema = self
+            .flags
+            .iter()
+            .map(|(name, default)| {
+                js_sys::Array::of2(&JsValue::from_str(name), &default.into())
+            })
+            .collect::<js_sys::Array>();
+
+        // a flag that's unknown, or given a value of another type, throws
+        let parsed = ns
+            .flags(&schema)
+            .map_err(|e| NsError::from(e).to_string())?;
+        let flags = objects::get_object::<T>(parsed)
+            .map_err(|e| e.to_string())?;
+
+        for check in &self.checks {
+            check(&flags)?;
+        }
+
+        Ok(flags)
+    }
+}
@@ -0,0 +1,111 @@
+//! The bindings for the functions of the game, as `ns` has them. What every
+//! script goes by is bound here, and the rest is grouped by what it's for,
+//! with the ones that the player doesn't need behind the `extended-ns`
+//! feature, for other scripts to build on.
+//!
+//! The functions keep the names that the game gives them.
+
+#![allow(non_snake_case)]
+
+pub mod args;
+mod bindings;
+pub mod files;
+pub mod flags;
+pub mod objects;
+#[cfg(feature = "extended-ns")]
+pub mod player;
+pub mod ports;
+mod print;
+pub mod processes;
+pub mod ram;
+mod safe;
+#[cfg(feature = "extended-ns")]
+pub mod servers;
+pub mod sleep;
+pub mod ui;
+
+use wasm_bindgen::prelude::*;
+
+pub use self::safe::{
+    Call,
+    NsError,
+    SafeNs,
+};
+use crate::Killed;
+
+// thank you github.com/paulcdejean
+#[wasm_bindgen]
+extern "C" {
+    #[derive(Clone)]
+    pub type NS;
+
+    #[wasm_bindgen(method)]
+    pub fn print(
+        this: &NS,
+        print: &str,
+    );
+
+    #[wasm_bindgen(method)]
+    pub fn printRaw(
+        this: &NS,
+        node: &JsValue,
+    );
+
+    #[wasm_bindgen(method)]
+    pub fn tprintRaw(
+        this: &NS,
+        node: &JsValue,
+    );
+
+    #[wasm_bindgen(method)]
+    pub fn clearLog(
+        this: &NS,
+    );
+
+    #[wasm_bindgen(method)]
+    pub fn tprint(
+        this: &NS,
+        print: &str,
+    );
+
+    #[wasm_bindgen(method)]
+    pub fn disableLog(
+        ns: &NS,
+        func: &str,
+    );
+
+    #[wasm_bindgen(method, catch, js_name = sleep)]
+    async fn try_sleep(
+        ns: &NS,
+        millis: f64,
+    ) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method)]
+    pub fn atExit(
+        ns: &NS,
+        f: &JsValue,
+    );
+
+    /// The arguments that the script was run with.
+    #[wasm_bindgen(method, getter)]
+    pub fn args(ns: &NS) -> js_sys::Array;
+
+    /// Parses the arguments by `schema`, an array of the flags, each as the
+    /// name and the default.
+    #[wasm_bindgen(method, catch)]
+    pub fn flags(
+        ns: &NS,
+        schema: &js_sys::Array,
+    ) -> Result<JsValue, JsValue>;
+}
+
+impl NS {
+    /// Sleeps for `millis`, fractions of a millisecond and all, for the
+    /// frames to keep to their timeline.
+    pub async fn sleep(
+        &self,
+        millis: impl Into<f64>,
+    ) -> Result<(), Killed> {
+        self.try_sleep(millis.into()).await.map_err(|_| Killed)
+    }
+}
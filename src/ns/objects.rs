@@ -0,0 +1,119 @@
+//! The objects that the functions of the game hand back, read into structs
+//! of their own, so that a field that's missing or of another type turns up
+//! as an error rather than as a value that quietly isn't there.
+
+use serde::{
+    de::DeserializeOwned,
+    Deserialize,
+};
+use wasm_bindgen::JsValue;
+
+use super::NsError;
+
+/// Reads `value` into a `T`.
+pub fn get_object<T: DeserializeOwned>(value: JsValue) -> Result<T, NsError> {
+    serde_wasm_bindgen::from_value(value).map_err(NsError::unexpected)
+}
+
+/// Reads the field `name` of `object` into a `T`.
+pub fn get_field<T: DeserializeOwned>(
+    object: &JsValue,
+    name: &str,
+) -> Result<T, NsError> {
+    get_object(js_sys::Reflect::get(object, &name.into())?)
+}
+
+/// Where the tail window of a script is, and how large, in pixels.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TailProperties {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The font and the spacing of the game, as `ns.ui.getStyles` has them.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Styles {
+    pub font_size: f64,
+    pub line_height: Option<f64>,
+    pub font_family: Option<String>,
+}
+
+/// A few of the colours of the game, each as CSS, as `ns.ui.getTheme` has
+/// them.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Theme {
+    pub primary: Option<String>,
+    pub secondary: Option<String>,
+    #[serde(rename = "backgroundprimary")]
+    pub background_primary: Option<String>,
+}
+
+/// A server, as `ns.getServer` has it. What's only there for the servers
+/// that can be hacked is missing for the others.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Server {
+    pub hostname: String,
+    pub ip: String,
+    pub organization_name: String,
+    pub is_connected_to: bool,
+    pub has_admin_rights: bool,
+    pub purchased_by_player: bool,
+    pub backdoor_installed: Option<bool>,
+    pub max_ram: f64,
+    pub ram_used: f64,
+    pub cpu_cores: u32,
+    pub money_available: Option<f64>,
+    pub money_max: Option<f64>,
+    pub hack_difficulty: Option<f64>,
+    pub min_difficulty: Option<f64>,
+    pub required_hacking_skill: Option<f64>,
+    pub num_open_ports_required: Option<u32>,
+    pub open_port_count: Option<u32>,
+}
+
+/// How much health the player has, and how much they can have.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Health {
+    pub current: f64,
+    pub max: f64,
+}
+
+/// The skills of the player, by level.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Skills {
+    pub hacking: f64,
+    pub strength: f64,
+    pub defense: f64,
+    pub dexterity: f64,
+    pub agility: f64,
+    pub charisma: f64,
+    pub intelligence: f64,
+}
+
+/// The player, as `ns.getPlayer` has them.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Player {
+    pub money: f64,
+    pub city: String,
+    pub location: String,
+    pub hp: Health,
+    pub skills: Skills,
+    #[serde(default)]
+    pub factions: Vec<String>,
+    /// How long the game has been played, in milliseconds.
+    pub total_playtime: Option<f64>,
+}
+
+/// The version of the game, as `ns.ui.getGameInfo` has it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct GameInfo {
+    pub version: String,
+    pub commit: String,
+    /// `Browser` or `Steam`.
+    pub platform: String,
+}
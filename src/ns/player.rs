@@ -0,0 +1,24 @@
+//! The player, and what there is to know about them.
+
+use wasm_bindgen::JsValue;
+
+use super::{
+    objects::{
+        self,
+        Player,
+    },
+    NsError,
+    SafeNs,
+};
+
+super::bindings::ns_bindings! {
+    fn get_player_object = getPlayer() -> JsValue;
+}
+
+impl SafeNs {
+    /// The money, the whereabouts, the health, and the skills of the player.
+    pub fn get_player(&self) -> Result<Player, NsError> {
+        objects::get_object(self.get_player_object()?)
+            .map_err(|e| e.during("getPlayer", &[]))
+    }
+}
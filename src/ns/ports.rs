@@ -0,0 +1,181 @@
+//! The ports that scripts talk to each other through, which hold their data
+//! in order until it's read off them, and [`Port`], which carries messages
+//! of a type of its own through one.
+
+use std::marker::PhantomData;
+
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use wasm_bindgen::prelude::*;
+
+use super::{
+    NsError,
+    SafeNs,
+    NS,
+};
+use crate::Killed;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Takes the oldest data off the port, which is `"NULL PORT DATA"` if
+    /// there's none.
+    #[wasm_bindgen(method)]
+    pub fn readPort(
+        ns: &NS,
+        port: u32,
+    ) -> JsValue;
+
+    /// Puts the data on the port, returning the data that it pushed off the
+    /// other end for the port being full, if any.
+    #[wasm_bindgen(method)]
+    pub fn writePort(
+        ns: &NS,
+        port: u32,
+        data: &JsValue,
+    ) -> JsValue;
+
+    /// The oldest data on the port, left on it.
+    #[wasm_bindgen(method)]
+    pub fn peek(
+        ns: &NS,
+        port: u32,
+    ) -> JsValue;
+
+    #[wasm_bindgen(method)]
+    pub fn clearPort(
+        ns: &NS,
+        port: u32,
+    );
+}
+
+super::bindings::ns_bindings! {
+    pub fn read_port = readPort(port: u32) -> JsValue;
+    pub fn write_port = writePort(port: u32, data: &JsValue) -> JsValue;
+    pub fn peek = peek(port: u32) -> JsValue;
+    pub fn clear_port = clearPort(port: u32);
+
+    /// Resolves once something gets written to the port.
+    pub async fn next_port_write = nextPortWrite(port: u32);
+}
+
+/// What `readPort` and `peek` hand back when the port is empty.
+pub const EMPTY_PORT: &str = "NULL PORT DATA";
+
+/// How often [`Port::next_message`] checks the port for a game that can't
+/// tell it when it gets written to, in milliseconds.
+const POLL_MS: u32 = 100;
+
+/// A port that carries messages of a type of its own, each as JSON.
+///
+/// Data that isn't JSON, like the `"pause"` that another script might write
+/// with `ns.writePort`, gets read as it is, so a `Port<String>` takes the
+/// commands written by hand as well as the ones it writes itself. Whatever
+/// doesn't read as a `T` gets handed back as it was on the port.
+pub struct Port<T> {
+    ns: SafeNs,
+    number: u32,
+    message: PhantomData<fn(T) -> T>,
+}
+
+impl<T> Clone for Port<T> {
+    fn clone(&self) -> Port<T> {
+        Port {
+            ns: self.ns.clone(),
+            number: self.number,
+            message: PhantomData,
+        }
+    }
+}
+
+impl<T> Port<T> {
+    pub fn new(
+        ns: &NS,
+        number: u32,
+    ) -> Port<T> {
+        Port {
+            ns: SafeNs::new(ns.clone()),
+            number,
+            message: PhantomData,
+        }
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn clear(&self) {
+        let _ = self.ns.clear_port(self.number);
+    }
+}
+
+impl<T: Serialize> Port<T> {
+    /// Puts the message on the port, pushing the oldest one off the other
+    /// end if the port is full.
+    pub fn write(
+        &self,
+        message: &T,
+    ) -> Result<(), NsError> {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        let value = message
+            .serialize(&serializer)
+            .map_err(NsError::unexpected)?;
+        let json = js_sys::JSON::stringify(&value)?;
+
+        self.ns.write_port(self.number, &json.into())?;
+        Ok(())
+    }
+}
+
+impl<T: DeserializeOwned> Port<T> {
+    /// Takes the oldest message off the port, unless it's empty.
+    pub fn try_read(&self) -> Option<Result<T, JsValue>> {
+        decode(self.ns.read_port(self.number).ok()?)
+    }
+
+    /// The oldest message on the port, left on it.
+    pub fn peek(&self) -> Option<Result<T, JsValue>> {
+        decode(self.ns.peek(self.number).ok()?)
+    }
+
+    /// Takes every message off the port, oldest first.
+    pub fn drain(&self) -> impl Iterator<Item = Result<T, JsValue>> + '_ {
+        std::iter::from_fn(|| self.try_read())
+    }
+
+    /// Waits for a message and takes it off the port, through
+    /// `nextPortWrite` where the game has it, and by checking every so often
+    /// where it doesn't.
+    pub async fn next_message(&self) -> Result<Result<T, JsValue>, Killed> {
+        loop {
+            if let Some(message) = self.try_read() {
+                return Ok(message);
+            }
+
+            match self.ns.next_port_write(self.number).await {
+                Ok(()) => {},
+                Err(NsError::Killed) => return Err(Killed),
+                Err(_) => self.ns.ns().sleep(POLL_MS).await?,
+            }
+        }
+    }
+}
+
+/// Reads a message as JSON, or as it is if it isn't JSON, unless it's what
+/// an empty port hands back.
+fn decode<T: DeserializeOwned>(data: JsValue) -> Option<Result<T, JsValue>> {
+    if data.as_string().as_deref() == Some(EMPTY_PORT) {
+        return None;
+    }
+
+    let parsed = data
+        .as_string()
+        .and_then(|json| js_sys::JSON::parse(&json).ok())
+        .and_then(|parsed| serde_wasm_bindgen::from_value(parsed).ok());
+
+    Some(match parsed {
+        Some(message) => Ok(message),
+        None => serde_wasm_bindgen::from_value(data.clone()).map_err(|_| data),
+    })
+}
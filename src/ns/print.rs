@@ -0,0 +1,10 @@
+//! Printing to the log and to the terminal as [`SafeNs`] does it, for what
+//! prints on behalf of a script that can have been killed in the meantime,
+//! which the game throws at rather than printing for.
+//!
+//! [`SafeNs`]: super::SafeNs
+
+super::bindings::ns_bindings! {
+    pub fn print = print(text: &str);
+    pub fn tprint = tprint(text: &str);
+}
@@ -0,0 +1,236 @@
+//! Starting, looking into, and killing scripts, and `Child`, which keeps
+//! an eye on a script that this one started.
+//!
+//! What only the jukebox calls is behind its feature, `Child` included,
+//! for the RAM of the functions to go along with it, as [`ram`](super::ram)
+//! has it.
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use super::{
+    args::ScriptArg,
+    objects,
+    NsError,
+    SafeNs,
+    NS,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    #[cfg(feature = "jukebox")]
+    #[wasm_bindgen(method)]
+    pub fn getScriptName(ns: &NS) -> String;
+
+    /// The script that's running this, with its tail window in
+    /// `tailProperties`.
+    #[wasm_bindgen(method, catch)]
+    pub fn getRunningScript(ns: &NS) -> Result<JsValue, JsValue>;
+
+    /// Runs the script on the server that this one runs on, returning its
+    /// pid, which is 0 if it couldn't be started.
+    #[cfg(feature = "jukebox")]
+    #[wasm_bindgen(method, variadic)]
+    pub fn run(
+        ns: &NS,
+        script: &str,
+        threads: u32,
+        args: &js_sys::Array,
+    ) -> u32;
+
+    /// Runs the script on `host`, returning its pid like `ns.run`.
+    #[wasm_bindgen(method, variadic)]
+    pub fn exec(
+        ns: &NS,
+        script: &str,
+        host: &str,
+        threads: u32,
+        args: &js_sys::Array,
+    ) -> u32;
+
+    /// The scripts running on the server of this one, unless `host` is
+    /// given, each with its `filename`, `pid`, `threads`, and `args`.
+    #[wasm_bindgen(method)]
+    pub fn ps(
+        ns: &NS,
+        host: Option<&str>,
+    ) -> js_sys::Array;
+
+    #[cfg(feature = "jukebox")]
+    #[wasm_bindgen(method)]
+    pub fn isRunning(
+        ns: &NS,
+        pid: u32,
+    ) -> bool;
+
+    #[cfg(feature = "jukebox")]
+    #[wasm_bindgen(method)]
+    pub fn kill(
+        ns: &NS,
+        pid: u32,
+    ) -> bool;
+
+    /// How much RAM a thread of the script takes, in GB, which is 0 if
+    /// there's no such script.
+    #[wasm_bindgen(method)]
+    pub fn getScriptRam(
+        ns: &NS,
+        script: &str,
+        host: Option<&str>,
+    ) -> f64;
+
+    #[cfg(feature = "jukebox")]
+    #[wasm_bindgen(method, catch, variadic, js_name = run)]
+    fn try_run(
+        ns: &NS,
+        script: &str,
+        threads: u32,
+        args: &js_sys::Array,
+    ) -> Result<u32, JsValue>;
+
+    #[wasm_bindgen(method, catch, variadic, js_name = exec)]
+    fn try_exec(
+        ns: &NS,
+        script: &str,
+        host: &str,
+        threads: u32,
+        args: &js_sys::Array,
+    ) -> Result<u32, JsValue>;
+}
+
+super::bindings::ns_bindings! {
+    pub fn get_running_script = getRunningScript() -> JsValue;
+    pub fn ps = ps(host: Option<&str>) -> js_sys::Array;
+    pub fn get_script_ram = getScriptRam(
+        script: &str,
+        host: Option<&str>,
+    ) -> f64;
+}
+
+// a module of their own, as every invocation takes one
+#[cfg(feature = "jukebox")]
+mod jukebox {
+    super::super::bindings::ns_bindings! {
+        pub fn get_script_name = getScriptName() -> String;
+        pub fn is_running = isRunning(pid: u32) -> bool;
+        pub fn kill = kill(pid: u32) -> bool;
+    }
+}
+
+// these get bound by hand for taking however many arguments, but still by
+// name, for the game to count their RAM
+impl SafeNs {
+    /// Runs the script with `args`, returning its pid like [`NS::run`].
+    #[cfg(feature = "jukebox")]
+    pub fn run(
+        &self,
+        script: &str,
+        threads: u32,
+        args: &[JsValue],
+    ) -> Result<u32, NsError> {
+        let args = args.iter().collect::<js_sys::Array>();
+
+        self.ns().try_run(script, threads, &args).map_err(|e| {
+            NsError::from(e).during("run", &[&script, &threads, &args])
+        })
+    }
+
+    /// Runs the script on `host` with `args`, returning its pid like
+    /// `ns.run`.
+    pub fn exec(
+        &self,
+        script: &str,
+        host: &str,
+        threads: u32,
+        args: &[JsValue],
+    ) -> Result<u32, NsError> {
+        let args = args.iter().collect::<js_sys::Array>();
+
+        self.ns().try_exec(script, host, threads, &args).map_err(|e| {
+            NsError::from(e).during("exec", &[&script, &host, &threads, &args])
+        })
+    }
+}
+
+/// A script running somewhere, as `ns.ps` has it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProcessInfo {
+    pub filename: String,
+    pub pid: u32,
+    pub threads: u32,
+    pub args: Vec<ScriptArg>,
+}
+
+impl SafeNs {
+    /// The scripts running on the server of this one, unless `host` is
+    /// given.
+    pub fn processes(
+        &self,
+        host: Option<&str>,
+    ) -> Result<Vec<ProcessInfo>, NsError> {
+        objects::get_object(self.ps(host)?.into())
+            .map_err(|e| e.during("ps", &[&host]))
+    }
+}
+
+/// A script started by this one, to keep an eye on.
+#[cfg(feature = "jukebox")]
+pub struct Child {
+    ns: SafeNs,
+    pid: u32,
+}
+
+#[cfg(feature = "jukebox")]
+impl Child {
+    /// Runs the script on the server of this one, unless it couldn't be
+    /// started, most likely for the lack of RAM.
+    pub fn run(
+        ns: &SafeNs,
+        script: &str,
+        threads: u32,
+        args: &[JsValue],
+    ) -> Result<Option<Child>, NsError> {
+        let pid = ns.run(script, threads, args)?;
+        Ok(Child::started(ns, pid))
+    }
+
+    /// Runs the script on `host`, like [`Child::run`].
+    pub fn exec(
+        ns: &SafeNs,
+        script: &str,
+        host: &str,
+        threads: u32,
+        args: &[JsValue],
+    ) -> Result<Option<Child>, NsError> {
+        let pid = ns.exec(script, host, threads, args)?;
+        Ok(Child::started(ns, pid))
+    }
+
+    /// The script by its pid, which is 0 for one that didn't start.
+    fn started(
+        ns: &SafeNs,
+        pid: u32,
+    ) -> Option<Child> {
+        (pid != 0).then(|| {
+            Child {
+                ns: ns.clone(),
+                pid,
+            }
+        })
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Whether the script is still running, which it doesn't count as if the
+    /// game can't tell.
+    pub fn is_running(&self) -> bool {
+        self.ns.is_running(self.pid).unwrap_or(false)
+    }
+
+    /// Kills the script, returning whether it was running.
+    pub fn kill(&self) -> bool {
+        self.ns.kill(self.pid).unwrap_or(false)
+    }
+}
@@ -0,0 +1,149 @@
+//! How much RAM the functions of the game cost a script, which the game
+//! works out by looking for their names in the script, whether or not they
+//! ever get called. The script of the module only names the functions that
+//! are bound and used, so leaving features out of the build leaves their
+//! functions out of the RAM along with them.
+//!
+//! A function that gets looked up by a name put together at runtime doesn't
+//! get counted, and the game kills the script for calling it, so everything
+//! gets bound by name.
+
+use super::{
+    objects::get_field,
+    NS,
+};
+
+/// What any script costs, before the functions that it names.
+pub const BASE_GB: f64 = 1.6;
+
+/// What a function costs, in GB a thread.
+pub struct Cost {
+    pub name: &'static str,
+    pub gb: f64,
+    /// What the function is there for, if it's there for a part of the
+    /// player that can be left out.
+    pub used_by: Option<&'static str>,
+    /// The feature that leaves the function out of the build, if any.
+    pub feature: Option<&'static str>,
+}
+
+const fn cost(
+    name: &'static str,
+    gb: f64,
+) -> Cost {
+    Cost {
+        name,
+        gb,
+        used_by: None,
+        feature: None,
+    }
+}
+
+const fn optional(
+    name: &'static str,
+    gb: f64,
+    used_by: &'static str,
+    feature: &'static str,
+) -> Cost {
+    Cost {
+        name,
+        gb,
+        used_by: Some(used_by),
+        feature: Some(feature),
+    }
+}
+
+/// The functions that the crate binds, with their costs as of version 2 of
+/// the game. The ones for the tail window, the ports, and `ns.ui` are free.
+pub const COSTS: &[Cost] = &[
+    cost("print", 0.),
+    cost("printRaw", 0.),
+    cost("tprint", 0.),
+    cost("tprintRaw", 0.),
+    cost("clearLog", 0.),
+    cost("disableLog", 0.),
+    cost("sleep", 0.),
+    cost("asleep", 0.),
+    cost("atExit", 0.),
+    cost("flags", 0.),
+    cost("read", 0.),
+    cost("write", 0.),
+    cost("mv", 0.),
+    cost("readPort", 0.),
+    cost("writePort", 0.),
+    cost("peek", 0.),
+    cost("clearPort", 0.),
+    cost("nextPortWrite", 0.),
+    cost("getRunningScript", 0.3),
+    cost("getScriptRam", 0.1),
+    cost("fileExists", 0.1),
+    cost("ls", 0.2),
+    cost("rm", 1.),
+    cost("exec", 1.3),
+    cost("ps", 0.2),
+    optional("getScriptName", 1., "the jukebox", "jukebox"),
+    optional("run", 1., "the jukebox", "jukebox"),
+    optional("isRunning", 0.1, "the jukebox", "jukebox"),
+    optional("kill", 0.5, "the jukebox", "jukebox"),
+    optional("getHostname", 0.05, "other scripts", "extended-ns"),
+    optional("scan", 0.2, "other scripts", "extended-ns"),
+    optional("getServer", 2., "other scripts", "extended-ns"),
+    optional("getPlayer", 0.5, "other scripts", "extended-ns"),
+];
+
+/// What the script costs, and which of the functions that cost anything it
+/// names.
+pub struct Report {
+    pub filename: String,
+    pub gb: f64,
+    pub costs: Vec<&'static Cost>,
+}
+
+impl Report {
+    /// Looks into the script that's running.
+    pub fn new(ns: &NS) -> Option<Report> {
+        let script = ns.getRunningScript().ok()?;
+        let filename = get_field::<String>(&script, "filename").ok()?;
+        let source = ns.read(&filename);
+
+        let costs = COSTS
+            .iter()
+            .filter(|c| 0. < c.gb && source.contains(&format!(".{}(", c.name)))
+            .collect();
+
+        Some(Report {
+            gb: ns.getScriptRam(&filename, None),
+            filename,
+            costs,
+        })
+    }
+
+    /// The functions that cost the most, first, each with why it's there.
+    pub fn breakdown(&self) -> String {
+        let mut costs = self.costs.clone();
+        costs.sort_by(|a, b| b.gb.total_cmp(&a.gb));
+
+        let mut breakdown = format!(
+            "{} takes {:.2} GB a thread\n  {:>5.2} GB for any script\n",
+            self.filename, self.gb, BASE_GB,
+        );
+
+        for cost in costs {
+            breakdown += &format!("  {:>5.2} GB for {}", cost.gb, cost.name);
+
+            if let (Some(used_by), Some(feature)) = (cost.used_by, cost.feature)
+            {
+                breakdown += &format!(
+                    ", for {}, which building without the {} feature leaves \
+                     out",
+                    used_by, feature,
+                );
+            }
+
+            breakdown.push('\n');
+        }
+
+        breakdown.pop();
+        breakdown
+    }
+}
@@ -0,0 +1,207 @@
+//! [`SafeNs`], the functions of the game as ones that hand back what they
+//! throw, rather than throwing it on through the module and taking the
+//! script down with it.
+
+use std::fmt;
+
+use wasm_bindgen::{
+    prelude::*,
+    JsCast,
+};
+
+use super::NS;
+use crate::Killed;
+
+/// How long an argument gets in a [`Call`], past which it's cut short, as
+/// what gets written to a file can run to megabytes.
+const MAX_ARG_LEN: usize = 40;
+
+/// A call of a function of the game, by its name in the game, for telling
+/// which one it was that went wrong, and with what.
+#[derive(Clone, Debug)]
+pub struct Call {
+    pub function: &'static str,
+    /// The arguments, as Rust prints them.
+    pub args: Vec<String>,
+}
+
+impl Call {
+    pub fn new(
+        function: &'static str,
+        args: &[&dyn fmt::Debug],
+    ) -> Call {
+        let args = args
+            .iter()
+            .map(|arg| {
+                let arg = format!("{:?}", arg);
+                match arg.char_indices().nth(MAX_ARG_LEN) {
+                    Some((end, _)) => format!("{}...", &arg[.. end]),
+                    None => arg,
+                }
+            })
+            .collect();
+
+        Call { function, args }
+    }
+}
+
+impl fmt::Display for Call {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}({})", self.function, self.args.join(", "))
+    }
+}
+
+/// What a function of the game threw, along with the call of it, unless it
+/// didn't come of one.
+#[derive(Debug)]
+pub enum NsError {
+    /// The script got killed while waiting on the function, which the game
+    /// rejects what it was waiting on with.
+    Killed,
+    /// The function threw, with the message of what it threw.
+    Thrown {
+        call: Option<Call>,
+        message: String,
+    },
+    /// The function handed back something other than what it should have,
+    /// as a game of another version might.
+    Unexpected {
+        call: Option<Call>,
+        message: String,
+    },
+}
+
+impl NsError {
+    pub fn unexpected(message: impl ToString) -> NsError {
+        NsError::Unexpected {
+            call: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// The error, as one of calling `function` with `args`, unless it's
+    /// already known to be of another call.
+    pub fn during(
+        mut self,
+        function: &'static str,
+        args: &[&dyn fmt::Debug],
+    ) -> NsError {
+        if let NsError::Thrown { call, .. } | NsError::Unexpected { call, .. } =
+            &mut self
+        {
+            call.get_or_insert_with(|| Call::new(function, args));
+        }
+
+        self
+    }
+
+    /// The call that went wrong, if it's known.
+    pub fn call(&self) -> Option<&Call> {
+        match self {
+            NsError::Killed => None,
+            NsError::Thrown { call, .. } | NsError::Unexpected { call, .. } => {
+                call.as_ref()
+            },
+        }
+    }
+}
+
+impl From<JsValue> for NsError {
+    fn from(thrown: JsValue) -> NsError {
+        // the game rejects with one of these once the script is killed
+        let constructor = js_sys::Reflect::get(&thrown, &"constructor".into())
+            .ok()
+            .and_then(|c| c.dyn_into::<js_sys::Function>().ok())
+            .map(|c| String::from(c.name()));
+        if constructor.as_deref() == Some("ScriptDeath") {
+            return NsError::Killed;
+        }
+
+        let message = match thrown.dyn_ref::<js_sys::Error>() {
+            Some(error) => String::from(error.message()),
+            None => {
+                thrown.as_string().unwrap_or_else(|| format!("{:?}", thrown))
+            },
+        };
+
+        NsError::Thrown {
+            call: None,
+            message,
+        }
+    }
+}
+
+impl From<Killed> for NsError {
+    fn from(_: Killed) -> NsError {
+        NsError::Killed
+    }
+}
+
+impl fmt::Display for NsError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            NsError::Killed => write!(f, "the script got killed"),
+            NsError::Thrown {
+                call: Some(call),
+                message,
+            } => write!(f, "{} threw: {}", call, message),
+            NsError::Thrown {
+                call: None,
+                message,
+            } => write!(f, "{}", message),
+            NsError::Unexpected {
+                call: Some(call),
+                message,
+            } => write!(f, "{} handed back something else: {}", call, message),
+            NsError::Unexpected {
+                call: None,
+                message,
+            } => {
+                write!(f, "the game handed back something else: {}", message)
+            },
+        }
+    }
+}
+
+impl std::error::Error for NsError {}
+
+/// `ns`, with its functions handing back what they throw.
+#[derive(Clone)]
+pub struct SafeNs {
+    ns: NS,
+}
+
+impl SafeNs {
+    pub fn new(ns: NS) -> SafeNs {
+        SafeNs { ns }
+    }
+
+    /// `ns` itself, for the functions that can't throw.
+    pub fn ns(&self) -> &NS {
+        &self.ns
+    }
+
+    /// Sleeps for `millis`, unless the script gets killed in the meantime.
+    pub async fn sleep(
+        &self,
+        millis: impl Into<f64>,
+    ) -> Result<(), NsError> {
+        let millis = millis.into();
+        self.ns
+            .try_sleep(millis)
+            .await
+            .map_err(|e| NsError::from(e).during("sleep", &[&millis]))
+    }
+}
+
+impl From<NS> for SafeNs {
+    fn from(ns: NS) -> SafeNs {
+        SafeNs::new(ns)
+    }
+}
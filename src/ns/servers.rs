@@ -0,0 +1,33 @@
+//! The servers of the network, and what there is to know about them.
+
+use wasm_bindgen::JsValue;
+
+use super::{
+    objects::{
+        self,
+        Server,
+    },
+    NsError,
+    SafeNs,
+};
+
+super::bindings::ns_bindings! {
+    /// The server that the script runs on.
+    pub fn get_hostname = getHostname() -> String;
+
+    /// The servers next to `host`, or to the one of the script.
+    pub fn scan = scan(host: Option<&str>) -> js_sys::Array;
+
+    fn get_server_object = getServer(host: Option<&str>) -> JsValue;
+}
+
+impl SafeNs {
+    /// Everything about `host`, or about the server of the script.
+    pub fn get_server(
+        &self,
+        host: Option<&str>,
+    ) -> Result<Server, NsError> {
+        objects::get_object(self.get_server_object(host)?)
+            .map_err(|e| e.during("getServer", &[&host]))
+    }
+}
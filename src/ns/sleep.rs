@@ -0,0 +1,97 @@
+//! Sleeping, to a point in time as well as for a while, and sleeping until a
+//! port gets written to, for a script to answer a message without checking
+//! its port every so often.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use super::{
+    ports::EMPTY_PORT,
+    SafeNs,
+    NS,
+};
+use crate::Killed;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Resolves after `millis`, like `sleep`, but without keeping the other
+    /// functions from being called in the meantime.
+    #[wasm_bindgen(method, catch)]
+    fn asleep(
+        ns: &NS,
+        millis: f64,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = nextPortWrite)]
+    fn next_port_write_promise(
+        ns: &NS,
+        port: u32,
+    ) -> Result<js_sys::Promise, JsValue>;
+}
+
+/// What a sleep got cut short by, if anything.
+pub enum Wake {
+    /// The sleep ran its course.
+    Slept,
+    /// The port got written to, or already had something on it.
+    Written,
+}
+
+/// Sleeps for `millis`, which is as good as not sleeping at all if it's
+/// negative, since the page still gets a turn.
+pub async fn sleep_ms(
+    ns: &NS,
+    millis: f64,
+) -> Result<(), Killed> {
+    ns.sleep(millis.max(0.)).await
+}
+
+/// Sleeps until the instant, in milliseconds since the epoch, which is right
+/// away if it's gone by.
+pub async fn sleep_until(
+    ns: &NS,
+    at: f64,
+) -> Result<(), Killed> {
+    // a sleep can come back early by a bit
+    loop {
+        let left = at - js_sys::Date::now();
+        if left <= 0. {
+            return Ok(());
+        }
+
+        ns.sleep(left).await?;
+    }
+}
+
+/// Sleeps for `millis`, unless `port` gets written to before then. A game
+/// without `asleep` or `nextPortWrite` sleeps it all.
+pub async fn sleep_or_message(
+    ns: &NS,
+    millis: f64,
+    port: u32,
+) -> Result<Wake, Killed> {
+    if ns.peek(port).as_string().as_deref() != Some(EMPTY_PORT) {
+        return Ok(Wake::Written);
+    }
+
+    let (Ok(slept), Ok(next_write)) = (
+        ns.asleep(millis.max(0.)),
+        ns.next_port_write_promise(port),
+    )
+    else {
+        sleep_ms(ns, millis).await?;
+        return Ok(Wake::Slept);
+    };
+
+    let race = js_sys::Promise::race(&js_sys::Array::of2(&slept, &next_write));
+    let _ = JsFuture::from(race).await;
+
+    // unlike sleep, asleep doesn't give up once the script gets killed, but
+    // every other function of the game does, peek included
+    let data = SafeNs::new(ns.clone()).peek(port).map_err(|_| Killed)?;
+
+    Ok(match data.as_string().as_deref() == Some(EMPTY_PORT) {
+        true => Wake::Slept,
+        false => Wake::Written,
+    })
+}
@@ -0,0 +1,135 @@
+//! `ns.ui`, with the look of the game, the page that it's on, and the tail
+//! windows of the scripts.
+//!
+//! The functions for the tail window have been moving from `ns` over to
+//! `ns.ui`, so they get looked up at runtime, for the same script to work
+//! with the versions of the game from before and after. Whatever a version
+//! doesn't have comes back as nothing rather than as an error.
+
+use wasm_bindgen::prelude::*;
+
+use super::{
+    objects::{
+        get_object,
+        GameInfo,
+        Styles,
+        TailProperties,
+        Theme,
+    },
+    NS,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    pub type UserInterface;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn ui(this: &NS) -> UserInterface;
+
+    /// The colours of the game, each as CSS.
+    #[wasm_bindgen(method, catch, js_name = getTheme)]
+    pub fn get_theme(this: &UserInterface) -> Result<JsValue, JsValue>;
+
+    /// The font and the spacing of the game.
+    #[wasm_bindgen(method, catch, js_name = getStyles)]
+    pub fn get_styles(this: &UserInterface) -> Result<JsValue, JsValue>;
+
+    /// The width and the height of the page, in pixels.
+    #[wasm_bindgen(method, catch, js_name = windowSize)]
+    pub fn window_size(this: &UserInterface) -> Result<JsValue, JsValue>;
+
+    /// The version of the game, and what it's running on.
+    #[wasm_bindgen(method, catch, js_name = getGameInfo)]
+    pub fn get_game_info(this: &UserInterface) -> Result<JsValue, JsValue>;
+}
+
+pub fn theme(ns: &NS) -> Option<Theme> {
+    get_object(ns.ui().get_theme().ok()?).ok()
+}
+
+pub fn styles(ns: &NS) -> Option<Styles> {
+    get_object(ns.ui().get_styles().ok()?).ok()
+}
+
+/// The size of the page that the game is on, in pixels.
+pub fn window_size(ns: &NS) -> Option<(f64, f64)> {
+    let size = js_sys::Array::from(&ns.ui().window_size().ok()?);
+
+    Some((size.get(0).as_f64()?, size.get(1).as_f64()?))
+}
+
+/// The version of the game, which the older versions don't tell.
+pub fn game_info(ns: &NS) -> Option<GameInfo> {
+    get_object(ns.ui().get_game_info().ok()?).ok()
+}
+
+/// Calls `name` of `ns.ui` with `args`, or `old_name` of `ns` if the game
+/// is from before the function moved, handing back what it did unless it
+/// threw or neither is there.
+fn call_tail(
+    ns: &NS,
+    name: &str,
+    old_name: &str,
+    args: &[JsValue],
+) -> Option<JsValue> {
+    let ui = js_sys::Reflect::get(ns, &JsValue::from_str("ui"))
+        .ok()
+        .filter(|ui| ui.is_object());
+    let old = Some((JsValue::from(ns), old_name));
+
+    for (this, name) in [ui.map(|ui| (ui, name)), old].into_iter().flatten() {
+        let function = js_sys::Reflect::get(&this, &JsValue::from_str(name))
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+
+        if let Some(function) = function {
+            let args = args.iter().collect::<js_sys::Array>();
+            return function.apply(&this, &args).ok();
+        }
+    }
+
+    None
+}
+
+/// Opens the tail window of the script.
+pub fn open_tail(ns: &NS) {
+    call_tail(ns, "openTail", "tail", &[]);
+}
+
+pub fn close_tail(ns: &NS) {
+    call_tail(ns, "closeTail", "closeTail", &[]);
+}
+
+/// Where the tail window of the script is, and how large, unless it's
+/// closed or the game is from before there was a function for it.
+pub fn tail_properties(ns: &NS) -> Option<TailProperties> {
+    let properties =
+        call_tail(ns, "getTailProperties", "getTailProperties", &[])?;
+
+    get_object::<Option<TailProperties>>(properties).ok()?
+}
+
+/// Resizes the tail window, in pixels.
+pub fn resize_tail(
+    ns: &NS,
+    width: u32,
+    height: u32,
+) {
+    call_tail(ns, "resizeTail", "resizeTail", &[width.into(), height.into()]);
+}
+
+/// Moves the tail window, in pixels from the top left of the page.
+pub fn move_tail(
+    ns: &NS,
+    x: f64,
+    y: f64,
+) {
+    call_tail(ns, "moveTail", "moveTail", &[x.into(), y.into()]);
+}
+
+pub fn set_tail_title(
+    ns: &NS,
+    title: &str,
+) {
+    call_tail(ns, "setTailTitle", "setTitle", &[title.into()]);
+}
@@ -0,0 +1,82 @@
+//! Drawing a short message over the top right of the frames, for what the
+//! commands change, like the volume or where a seek went, for a couple of
+//! seconds after they come in.
+
+use std::borrow::Cow;
+
+use crate::ansi::{
+    self,
+    Piece,
+};
+
+/// How long a message stays up, in milliseconds.
+pub const OSD_SHOWN_MS: f64 = 2000.;
+
+/// Draws `message` over the end of the first row of `frame`, cut down to
+/// `width` if it's any longer, if there's a message.
+pub fn draw<'a>(
+    frame: &'a str,
+    message: Option<&str>,
+    width: u32,
+) -> Cow<'a, str> {
+    const RESET: &str = "\u{1b}[0m";
+
+    let Some(message) = message
+    else {
+        return Cow::Borrowed(frame);
+    };
+
+    let width = width as usize;
+    let message = format!(" {} ", message)
+        .chars()
+        .take(width)
+        .collect::<String>();
+    let kept = width - message.chars().count();
+
+    let (first, rest) = frame.split_once('\n').unwrap_or((frame, ""));
+
+    let mut output = String::with_capacity(frame.len() + message.len());
+    output += &take_columns(first, kept);
+    output += "\u{1b}[0;30;107m";
+    output += &message;
+    output += RESET;
+    output += "\n";
+    output += rest;
+
+    Cow::Owned(output)
+}
+
+/// The first `columns` characters of `row`, along with the codes before
+/// them, so they're drawn as they were.
+fn take_columns(
+    row: &str,
+    columns: usize,
+) -> String {
+    let mut taken = String::with_capacity(row.len());
+    let mut left = columns;
+
+    for piece in ansi::pieces(row) {
+        match piece {
+            Piece::Text(text) => {
+                let end = text
+                    .char_indices()
+                    .nth(left)
+                    .map_or(text.len(), |(index, _)| index);
+                left -= text[.. end].chars().count();
+                taken += &text[.. end];
+            },
+            Piece::Color(params) => {
+                taken += "\u{1b}[";
+                taken += params;
+                taken += "m";
+            },
+            Piece::Escape(escape) => taken += escape,
+        }
+
+        if left == 0 {
+            break;
+        }
+    }
+
+    taken
+}
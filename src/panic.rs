@@ -0,0 +1,36 @@
+//! Reporting panics to the terminal, since all that they'd otherwise leave
+//! behind is an `unreachable` trap in the browser console, with the player
+//! stopped mid-frame for no reason that the game shows.
+
+use wasm_bindgen::prelude::*;
+
+use crate::script;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(message: &str);
+}
+
+/// Reports every panic from here on to the terminal, for the script that
+/// was running, as well as to the browser console.
+pub fn install() {
+    std::panic::set_hook(Box::new(|info| {
+        let frame = script::with(|script| script.frame.get()).flatten();
+        let message = match frame {
+            Some(f) => format!("player crashed at frame {}: {}", f, info),
+            None => format!("player crashed: {}", info),
+        };
+
+        console_error(&message);
+
+        // the console is all there is once the script has been killed
+        let _ = script::with(|script| script.ns.tprint(&message));
+    }));
+}
+
+/// Sets the frame that the panics of the running script get reported at, or
+/// that there isn't one.
+pub fn set_frame(frame: Option<usize>) {
+    script::with(|script| script.frame.set(frame));
+}
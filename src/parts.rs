@@ -0,0 +1,167 @@
+//! Movies split over several files, for the ones that get too large for a
+//! single file to hold, or to keep in memory all at once.
+//!
+//! The file that gets played is then a manifest: a `#PARTS` line, followed by
+//! the filename of every part in order, e.g. `movie.part1.txt`. Put together,
+//! the parts make for the base64 of the movie, and each one only gets read as
+//! the playback comes up to it.
+//!
+//! A file can also hold a movie that's neither compressed nor base64, for
+//! the likes of spinners and banners written by hand, as a `#TEXT` line
+//! followed by the movie as it is, without any chunks, since the first line
+//! of its frames can just as well start with a `#`.
+
+use std::io::{
+    self,
+    Read,
+};
+
+use crate::NS;
+
+/// The first line of a manifest, which can't start a movie, as `#` isn't
+/// part of base64.
+pub const MANIFEST_LINE: &str = "#PARTS";
+
+/// The first line of a movie in plain text.
+pub const TEXT_LINE: &str = "#TEXT";
+
+/// Where the base64 of the movie is.
+pub enum Contents {
+    /// The movie is all in the file itself.
+    Whole(String),
+    /// The movie is all in the file itself, in plain text.
+    Text(String),
+    /// The movie is over the files, one after the other.
+    Parts(Vec<String>),
+}
+
+impl Contents {
+    /// Reads `filename`, which is either the movie or its manifest, unless
+    /// it's empty or isn't there at all.
+    pub fn read(
+        ns: &NS,
+        filename: &str,
+    ) -> Option<Contents> {
+        let mut contents = ns.read(filename);
+        if contents.is_empty() {
+            return None;
+        }
+
+        if contents.lines().next().map(|l| l.trim_end()) == Some(TEXT_LINE) {
+            let start = contents.find('\n').map_or(contents.len(), |n| n + 1);
+            contents.drain(.. start);
+
+            return Some(Contents::Text(contents));
+        }
+
+        let Some(manifest) = contents.strip_prefix(MANIFEST_LINE)
+        else {
+            return Some(Contents::Whole(contents));
+        };
+
+        let parts = manifest
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_owned())
+            .collect::<Vec<_>>();
+
+        match parts.is_empty() {
+            true => None,
+            false => Some(Contents::Parts(parts)),
+        }
+    }
+
+    /// How many bytes of the movie are held in memory as a whole, which
+    /// leaves out the part being read, if it comes in parts.
+    pub fn held(&self) -> usize {
+        match self {
+            Contents::Whole(contents) | Contents::Text(contents) => {
+                contents.len()
+            },
+            Contents::Parts(parts) => parts.iter().map(|p| p.len()).sum(),
+        }
+    }
+
+    /// Whether the movie is in plain text, rather than compressed and base64.
+    pub fn is_text(&self) -> bool {
+        matches!(self, Contents::Text(_))
+    }
+
+    /// Starts reading the movie over from its start, as it's stored.
+    pub fn reader<'a>(
+        &'a self,
+        ns: &'a NS,
+    ) -> Source<'a> {
+        match self {
+            Contents::Whole(contents) | Contents::Text(contents) => {
+                Source::Whole(contents.as_bytes())
+            },
+            Contents::Parts(parts) => {
+                Source::Parts {
+                    ns,
+                    parts,
+                    part: vec![],
+                    at: 0,
+                }
+            },
+        }
+    }
+}
+
+/// The movie as it's stored, read a part at a time if it comes in parts.
+pub enum Source<'a> {
+    Whole(&'a [u8]),
+    Parts {
+        ns: &'a NS,
+        /// The parts that are yet to be read.
+        parts: &'a [String],
+        /// The part being read, which gets dropped for the next one once it's
+        /// been read through.
+        part: Vec<u8>,
+        at: usize,
+    },
+}
+
+impl Read for Source<'_> {
+    fn read(
+        &mut self,
+        buf: &mut [u8],
+    ) -> io::Result<usize> {
+        let (ns, parts, part, at) = match self {
+            Source::Whole(contents) => return contents.read(buf),
+            Source::Parts {
+                ns,
+                parts,
+                part,
+                at,
+            } => (ns, parts, part, at),
+        };
+
+        while part.len() <= *at {
+            let Some((filename, rest)) = parts.split_first()
+            else {
+                return Ok(0);
+            };
+
+            let mut contents = ns.read(filename);
+            if contents.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("the part {} is empty or doesn't exist", filename),
+                ));
+            }
+
+            // the base64 carries on from one part to the next, without the
+            // newline that the file might end with
+            contents.truncate(contents.trim_end().len());
+            *part = contents.into_bytes();
+            *at = 0;
+            *parts = rest;
+        }
+
+        let read = (&part[*at ..]).read(buf)?;
+        *at += read;
+        Ok(read)
+    }
+}
@@ -0,0 +1,51 @@
+//! Making the movie brighter or darker, and upping or lowering its contrast,
+//! for `--brightness` and `--contrast`, by rewriting the colours of the
+//! characters as the frames go up, for an encode that came out too dark to
+//! be fixed without going back to the encoder.
+
+use crate::ansi::{
+    self,
+    Color,
+    Layer,
+};
+
+pub struct Adjustment {
+    /// How much gets added to every channel, from -1 to 1.
+    brightness: f64,
+    /// How much further every channel gets from the middle grey.
+    contrast: f64,
+}
+
+impl Adjustment {
+    /// The adjustment, unless it leaves the colours as they are.
+    pub fn new(
+        brightness: f64,
+        contrast: f64,
+    ) -> Option<Adjustment> {
+        (brightness != 0. || contrast != 1.).then_some(Adjustment {
+            brightness,
+            contrast,
+        })
+    }
+
+    /// Adjusts the colours of the characters of `frame`, leaving the ones
+    /// behind them be.
+    pub fn apply(
+        &self,
+        frame: &str,
+    ) -> String {
+        let channel = |c: u8| {
+            let c = (c as f64 / 255. - 0.5) * self.contrast + 0.5;
+            ((c + self.brightness).clamp(0., 1.) * 255.).round() as u8
+        };
+
+        ansi::recolor(frame, |layer, color| {
+            match (layer, color.rgb()) {
+                (Layer::Foreground, Some((r, g, b))) => {
+                    Color::Rgb(channel(r), channel(g), channel(b))
+                },
+                _ => color,
+            }
+        })
+    }
+}
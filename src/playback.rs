@@ -0,0 +1,339 @@
+//! The state of the playback that control commands act on.
+
+use crate::{
+    audio::Soundtrack,
+    control::{
+        self,
+        Command,
+        Seek,
+    },
+    format_time,
+    now_playing,
+    ns::sleep,
+    osd::OSD_SHOWN_MS,
+    performance_now,
+    Killed,
+    NS,
+};
+
+/// How often the control port gets checked while paused, in milliseconds,
+/// past answering a command as it gets written.
+pub const PAUSE_POLL_MS: u32 = 100;
+
+/// How long a panel stays up, in milliseconds.
+pub const PANEL_SHOWN_MS: f64 = 5000.;
+
+/// Something drawn over the frames for a few seconds.
+pub enum Panel {
+    Chapters,
+    Info,
+    /// The attribution of the movie, which goes up as it starts.
+    Attribution,
+}
+
+/// Replaying the part of the movie between two marks, in seconds.
+#[derive(Clone, Copy)]
+pub enum Repeat {
+    Off,
+    /// The first mark is down, and the second is yet to be.
+    From(f64),
+    Between(f64, f64),
+}
+
+pub struct Playback {
+    pub audio: Option<Box<dyn Soundtrack>>,
+    pub paused: bool,
+    /// The port that the commands come in on.
+    pub port: u32,
+    /// The port that the state of the playback goes up on.
+    pub now_playing_port: u32,
+    /// How much faster than its framerate the movie plays.
+    pub speed: f64,
+    /// Where the movie is at, in seconds, as kept up by the caller.
+    pub position: f64,
+    /// The seek for the caller to carry out, since only it knows how to get
+    /// the movie there.
+    pub seek: Option<Seek>,
+    /// The frames to step through after the seek, for the caller as well.
+    pub steps: i64,
+    /// Whether the movie starts over once it ends.
+    pub looping: bool,
+    /// Whether the frames only move on when stepped through, as slides.
+    pub manual: bool,
+    pub repeat: Repeat,
+    /// The language of the subtitles, if one was picked.
+    pub language: Option<String>,
+    pub subtitles_shown: bool,
+    /// Whether the status line goes under the frames.
+    pub status_shown: bool,
+    /// The panel being shown along with until when, as a timestamp.
+    pub panel: Option<(Panel, f64)>,
+    /// The message about the last command along with until when it's shown,
+    /// as a timestamp.
+    pub osd: Option<(String, f64)>,
+    /// The snapshot for the caller to write, to the file if one was given.
+    pub snapshot: Option<Option<String>>,
+    /// The instant for the caller to line the movie up with, in milliseconds
+    /// since the epoch.
+    pub sync_at: Option<f64>,
+}
+
+impl Playback {
+    pub fn new(audio: Option<Box<dyn Soundtrack>>) -> Playback {
+        Playback {
+            audio,
+            paused: false,
+            port: control::CONTROL_PORT,
+            now_playing_port: now_playing::NOW_PLAYING_PORT,
+            speed: 1.,
+            position: 0.,
+            seek: None,
+            steps: 0,
+            looping: false,
+            manual: false,
+            repeat: Repeat::Off,
+            language: None,
+            subtitles_shown: false,
+            status_shown: false,
+            panel: None,
+            osd: None,
+            snapshot: None,
+            sync_at: None,
+        }
+    }
+
+    /// Applies every command waiting on the control port.
+    pub fn poll(
+        &mut self,
+        ns: &NS,
+    ) {
+        for command in control::poll(ns, self.port) {
+            self.apply(command);
+        }
+    }
+
+    /// Applies the command. Resuming only clears [`Playback::paused`], since
+    /// only the caller knows where the soundtrack has to resume from.
+    pub fn apply(
+        &mut self,
+        command: Command,
+    ) {
+        if let Some(message) = self.message(&command) {
+            self.notify(message);
+        }
+
+        match command {
+            Command::Pause if !self.paused => {
+                self.paused = true;
+
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.pause();
+                }
+            },
+            Command::Pause => {},
+            Command::Resume if self.manual => {},
+            Command::Resume => self.paused = false,
+            Command::Volume(v) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.set_volume(v);
+                }
+            },
+            Command::Mute | Command::Unmute => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.set_muted(matches!(command, Command::Mute));
+                }
+            },
+            Command::Language(language) => {
+                self.subtitles_shown = language.is_some();
+                self.language = language;
+            },
+            Command::Subtitles(shown) => self.subtitles_shown = shown,
+            Command::Status(shown) => self.status_shown = shown,
+            Command::Step(frames) => {
+                self.apply(Command::Pause);
+                self.steps += frames;
+            },
+            Command::Speed(speed) => self.set_speed(speed),
+            Command::MarkRepeat => {
+                self.repeat = match self.repeat {
+                    Repeat::Off => Repeat::From(self.position),
+                    Repeat::From(a) if a < self.position => {
+                        Repeat::Between(a, self.position)
+                    },
+                    Repeat::From(_) => Repeat::From(self.position),
+                    Repeat::Between(..) => Repeat::Off,
+                };
+            },
+            Command::Repeat(Some((a, b))) => {
+                self.repeat = Repeat::Between(a, b)
+            },
+            Command::Repeat(None) => self.repeat = Repeat::Off,
+            Command::Seek(seek) => {
+                self.seek = Some(match self.seek {
+                    Some(pending) => pending.then(seek),
+                    None => seek,
+                });
+            },
+            Command::Chapters => self.show(Panel::Chapters),
+            Command::Info => self.show(Panel::Info),
+            Command::Snapshot(filename) => self.snapshot = Some(filename),
+            Command::Sync(at) => self.sync_at = Some(at),
+        }
+    }
+
+    /// What a command changes, to put up over the frames, for those that
+    /// change something that can't be seen otherwise. A seek gets its
+    /// message from the caller, once it's carried out.
+    fn message(
+        &self,
+        command: &Command,
+    ) -> Option<String> {
+        let audio = self.audio.is_some();
+
+        match command {
+            Command::Pause if !self.paused => Some("\u{275a}\u{275a}".into()),
+            Command::Resume if self.paused && !self.manual => {
+                Some("\u{25b6}".into())
+            },
+            Command::Volume(v) if audio => {
+                Some(format!("volume {:.0}%", v * 100.))
+            },
+            Command::Mute if audio => Some("muted".into()),
+            Command::Unmute if audio => Some("unmuted".into()),
+            Command::Speed(speed) => Some(format!("speed {}x", speed)),
+            Command::Subtitles(true) => Some("subtitles on".into()),
+            Command::Subtitles(false) => Some("subtitles off".into()),
+            Command::Language(Some(language)) => {
+                Some(format!("subtitles: {}", language))
+            },
+            Command::MarkRepeat => {
+                Some(match self.repeat {
+                    Repeat::Off => {
+                        format!("repeat from {}", format_time(self.position))
+                    },
+                    Repeat::From(a) if a < self.position => {
+                        format!(
+                            "repeat {} to {}",
+                            format_time(a),
+                            format_time(self.position),
+                        )
+                    },
+                    Repeat::From(_) => {
+                        format!("repeat from {}", format_time(self.position))
+                    },
+                    Repeat::Between(..) => "repeat off".into(),
+                })
+            },
+            Command::Repeat(None) => Some("repeat off".into()),
+            _ => None,
+        }
+    }
+
+    /// Puts `message` up over the frames, replacing whichever one was up.
+    pub fn notify(
+        &mut self,
+        message: String,
+    ) {
+        self.osd = Some((message, js_sys::Date::now() + OSD_SHOWN_MS));
+    }
+
+    /// The message to draw right now, if any.
+    pub fn osd(&self) -> Option<&str> {
+        self.osd
+            .as_ref()
+            .filter(|(_, until)| js_sys::Date::now() < *until)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Whether there's a seek or steps for the caller to carry out.
+    pub fn is_seeking(&self) -> bool {
+        self.seek.is_some() || self.steps != 0
+    }
+
+    /// Queues the seek back to the first mark once the playback gets past the
+    /// second, or to wherever it starts over from when it ends, returning
+    /// whether there was one.
+    pub fn repeat_if_due(
+        &mut self,
+        ended: bool,
+    ) -> bool {
+        let seek = match self.repeat {
+            Repeat::Between(a, b) if ended || b <= self.position => a,
+            _ if ended && self.looping => 0.,
+            _ => return false,
+        };
+
+        self.seek = Some(Seek::To(seek));
+        true
+    }
+
+    /// Sets the speed, for the soundtrack as well. The caller has to keep its
+    /// frames in time.
+    pub fn set_speed(
+        &mut self,
+        speed: f64,
+    ) {
+        self.speed = speed;
+
+        if let Some(audio) = self.audio.as_mut() {
+            audio.set_speed(speed);
+        }
+    }
+
+    /// Puts the panel up, replacing whichever one was already up.
+    pub fn show(
+        &mut self,
+        panel: Panel,
+    ) {
+        self.panel = Some((panel, js_sys::Date::now() + PANEL_SHOWN_MS));
+    }
+
+    /// The panel to draw right now, if any.
+    pub fn panel(&self) -> Option<&Panel> {
+        self.panel
+            .as_ref()
+            .filter(|(_, until)| js_sys::Date::now() < *until)
+            .map(|(panel, _)| panel)
+    }
+
+    /// Waits for as long as the playback is paused, returning how long that
+    /// was in milliseconds, unless the script gets killed in the meantime.
+    /// The soundtrack picks up again from `position`, so it lines up with the
+    /// frames rather than with where it got paused.
+    ///
+    /// A seek or a step cuts the wait short, leaving the playback paused, so
+    /// that the caller can show where it landed, and so do a snapshot and a
+    /// sync.
+    pub async fn wait_while_paused(
+        &mut self,
+        ns: &NS,
+        position: impl Fn(&Playback) -> f64,
+    ) -> Result<f64, Killed> {
+        if !self.paused {
+            return Ok(0.);
+        }
+
+        let paused_at = performance_now();
+
+        while self.paused
+            && !self.is_seeking()
+            && self.snapshot.is_none()
+            && self.sync_at.is_none()
+        {
+            sleep::sleep_or_message(ns, PAUSE_POLL_MS.into(), self.port)
+                .await?;
+            self.poll(ns);
+        }
+
+        if self.paused {
+            return Ok(performance_now() - paused_at);
+        }
+
+        let position = position(self);
+        if let Some(audio) = self.audio.as_mut() {
+            audio.resume(position);
+        }
+
+        Ok(performance_now() - paused_at)
+    }
+}
@@ -0,0 +1,78 @@
+//! Keeping up with the movie when the game lags, by going up only every so
+//! many frames rather than dropping them here and there.
+
+/// How many frames that went up make for a window, which the playback gets
+/// judged over.
+const WINDOW: usize = 30;
+
+/// The share of the frames of a window that can go dropped before the
+/// playback counts as falling behind.
+const BEHIND_SHARE: f64 = 0.25;
+
+/// How many windows in a row have to go without a dropped frame before the
+/// playback tries going up every frame again.
+const CLEAN_WINDOWS: usize = 3;
+
+/// The most frames that go by for every one that goes up.
+const MAX_STRIDE: usize = 4;
+
+pub struct Quality {
+    stride: usize,
+    /// The frames that went up, and that got dropped, in the window so far.
+    shown: usize,
+    dropped: usize,
+    clean_windows: usize,
+}
+
+impl Quality {
+    pub fn new() -> Quality {
+        Quality {
+            stride: 1,
+            shown: 0,
+            dropped: 0,
+            clean_windows: 0,
+        }
+    }
+
+    /// How many frames go by for every one that goes up.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Counts a frame that went up after `dropped` frames got dropped for
+    /// being late, halving or doubling the frames that go up as each window
+    /// comes to an end.
+    pub fn record(
+        &mut self,
+        dropped: usize,
+    ) {
+        self.shown += 1;
+        self.dropped += dropped;
+
+        if self.shown < WINDOW {
+            return;
+        }
+
+        let share = self.dropped as f64 / (self.shown + self.dropped) as f64;
+
+        if BEHIND_SHARE < share {
+            self.stride = (self.stride * 2).min(MAX_STRIDE);
+            self.clean_windows = 0;
+        }
+        else if self.dropped == 0 && 1 < self.stride {
+            self.clean_windows += 1;
+
+            // a player that just barely keeps up would go back and forth
+            if CLEAN_WINDOWS <= self.clean_windows {
+                self.stride /= 2;
+                self.clean_windows = 0;
+            }
+        }
+        else {
+            self.clean_windows = 0;
+        }
+
+        self.shown = 0;
+        self.dropped = 0;
+    }
+}
@@ -0,0 +1,126 @@
+//! Playback of radio files, which have a soundtrack but no frames.
+
+use crate::{
+    format_time,
+    now_playing,
+    ns::ui,
+    playback::Playback,
+    tail::CharSize,
+    Killed,
+    NS,
+};
+
+/// How often the elapsed time gets redrawn, in milliseconds.
+const REFRESH_MS: u32 = 250;
+
+/// The size of the tail window, in characters.
+const TAIL_COLUMNS: u32 = 30;
+const TAIL_ROWS: u32 = 2;
+
+pub async fn play_radio(
+    ns: &NS,
+    filename: &str,
+    mut playback: Playback,
+    char_size: CharSize,
+) -> Result<(), Killed> {
+    let Some(soundtrack) = playback.audio.as_mut()
+    else {
+        log::error!("{} has neither frames nor audio", filename);
+        return Ok(());
+    };
+
+    soundtrack.play();
+
+    if playback.paused {
+        soundtrack.pause();
+    }
+    let (width, height) = char_size.tail_size(TAIL_COLUMNS, TAIL_ROWS);
+    ui::resize_tail(ns, width, height);
+
+    let mut last_publish = f64::NEG_INFINITY;
+
+    loop {
+        playback.poll(ns);
+
+        // nothing gets put up while paused, so the state has to say so
+        // before the wait
+        if playback.paused {
+            now_playing::publish(ns, filename, None, &playback);
+            last_publish = js_sys::Date::now();
+        }
+
+        // without frames to line up with, the soundtrack carries on from
+        // wherever it got paused
+        playback
+            .wait_while_paused(ns, |p| {
+                p.audio.as_ref().map(|a| a.position()).unwrap_or(0.)
+            })
+            .await?;
+
+        let Some(position) = playback.audio.as_ref().map(|a| a.position())
+        else {
+            break;
+        };
+
+        playback.position = position;
+        playback.repeat_if_due(false);
+
+        // there are no frames to step through, take snapshots of, or line up
+        playback.steps = 0;
+        playback.snapshot = None;
+        playback.sync_at = None;
+
+        if let (Some(seek), Some(soundtrack)) =
+            (playback.seek.take(), playback.audio.as_mut())
+        {
+            let target = seek.target(position);
+            soundtrack.seek(target);
+
+            // audio that already ended has to be played again
+            if !playback.paused {
+                soundtrack.resume(target);
+            }
+        }
+
+        let Some(soundtrack) = playback.audio.as_ref()
+        else {
+            break;
+        };
+
+        let position = soundtrack.position();
+        let duration = soundtrack.duration();
+
+        let time = match duration {
+            Some(duration) => {
+                format!("{} / {}", format_time(position), format_time(duration))
+            },
+            None => format_time(position),
+        };
+
+        ns.clearLog();
+        ns.print(&format!("\u{266a} {}\n{}", filename, time));
+
+        let since_publish = js_sys::Date::now() - last_publish;
+        if now_playing::PUBLISH_INTERVAL_MS <= since_publish {
+            playback.position = position;
+            now_playing::publish(ns, filename, None, &playback);
+            last_publish = js_sys::Date::now();
+        }
+
+        if duration.is_some_and(|d| d <= position) {
+            playback.position = position;
+
+            if !playback.repeat_if_due(true) {
+                break;
+            }
+        }
+
+        ns.sleep(REFRESH_MS).await?;
+    }
+
+    if let Some(soundtrack) = playback.audio.as_mut() {
+        soundtrack.stop();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Sending a command to a player or to the jukebox, for
+//! `run bitoxide.js --remote seek 30`, the same as `remote.js` does without
+//! having to load the module.
+//!
+//! `--port <port>` goes to a player started with `--port`, `--instance
+//! <name>` to one started with `--instance`, and `--jukebox` to the jukebox.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    control::CONTROL_PORT,
+    instance,
+    ns::args,
+    NS,
+};
+
+const USAGE: &str = "usage: run bitoxide.js --remote [--port <port> | \
+                     --instance <name> | --jukebox] <command>...";
+
+#[wasm_bindgen]
+pub fn remote_rs(ns: &NS) {
+    // past the --remote, and with the numbers as they were typed
+    let args = args::args(ns).skip(1).map(|a| a.text()).collect::<Vec<_>>();
+    let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+
+    let (port, command) = match args.as_slice() {
+        #[cfg(feature = "jukebox")]
+        ["--jukebox", command @ ..] => {
+            (Some(crate::jukebox::JUKEBOX_PORT), command)
+        },
+        ["--port", port, command @ ..] => {
+            (port.parse().ok().filter(|p| 0 < *p), command)
+        },
+        ["--instance", name, command @ ..] => {
+            (Some(instance::port(name, CONTROL_PORT)), command)
+        },
+        command => (Some(CONTROL_PORT), command),
+    };
+
+    match port {
+        Some(port) if !command.is_empty() => {
+            ns.writePort(port, &command.join(" ").into());
+        },
+        _ => ns.tprint(USAGE),
+    }
+}
@@ -0,0 +1,150 @@
+//! Putting the frames up in the log of the tail window, either as they are,
+//! colour codes and all, or as React elements that the colours get read out
+//! into, which the log draws the same in every version of the game, true
+//! colour backgrounds included. Otherwise, they go up on a screen of their
+//! own over the page, away from the log, or in the terminal, one after the
+//! other, which only suits a movie of a few frames a second.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    ansi,
+    dom::Screen,
+    NS,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = React, js_name = createElement, variadic)]
+    fn create_element(
+        tag: &str,
+        props: &JsValue,
+        children: &js_sys::Array,
+    ) -> JsValue;
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum Mode {
+    /// Printing the frames with their colour codes.
+    #[default]
+    Ansi,
+    /// Printing the frames as React elements, through `printRaw`.
+    React,
+    /// Drawing the frames on a screen of their own.
+    Dom,
+    /// Printing the frames to the terminal as React elements, through
+    /// `tprintRaw`, with no tail window at all.
+    Terminal,
+}
+
+impl Mode {
+    pub fn parse(mode: &str) -> Option<Mode> {
+        match mode {
+            "ansi" => Some(Mode::Ansi),
+            "react" => Some(Mode::React),
+            "dom" => Some(Mode::Dom),
+            _ => None,
+        }
+    }
+}
+
+/// Makes an object out of the properties that have a value.
+fn object<'a>(
+    properties: impl IntoIterator<Item = (&'a str, Option<JsValue>)>,
+) -> JsValue {
+    let object = js_sys::Object::new();
+
+    for (key, value) in properties {
+        if let Some(value) = value {
+            let _ = js_sys::Reflect::set(&object, &key.into(), &value);
+        }
+    }
+
+    object.into()
+}
+
+/// Reads the colours of `text` out into a span for every run drawn alike.
+fn element(text: &str) -> JsValue {
+    let spans = ansi::runs(text).into_iter().map(|(style, run)| {
+        let (foreground, background) = style.colors();
+        let css = object([
+            ("color", foreground.css().map(JsValue::from)),
+            ("backgroundColor", background.css().map(JsValue::from)),
+            ("fontWeight", style.bold.then(|| JsValue::from("bold"))),
+        ]);
+        let props = object([("style", Some(css))]);
+
+        create_element("span", &props, &js_sys::Array::of1(&run.into()))
+    });
+
+    // the lines of the frame keep to their own lines, spaces and all
+    let css = object([("whiteSpace", Some(JsValue::from("pre")))]);
+    let props = object([("style", Some(css))]);
+
+    create_element("div", &props, &spans.collect())
+}
+
+/// How many frames go up in the log between clearings of it. The frames are
+/// as tall as the tail window, which keeps to the bottom of the log, so the
+/// latest one fills it regardless, and clearing the log for every frame
+/// leaves it blank for a moment on slower machines.
+const CLEAR_EVERY: usize = 30;
+
+/// What the frames get put up with.
+pub struct Renderer {
+    mode: Mode,
+    /// The screen for [`Mode::Dom`], which is left out for the log if the
+    /// page can't be got to.
+    screen: Option<Screen>,
+    /// How many frames went up in the log since it was last cleared.
+    printed: usize,
+}
+
+impl Renderer {
+    pub fn new(mode: Mode) -> Renderer {
+        let screen = match mode {
+            Mode::Dom => Screen::new(),
+            Mode::Ansi | Mode::React | Mode::Terminal => None,
+        };
+
+        Renderer {
+            mode,
+            screen,
+            printed: 0,
+        }
+    }
+
+    /// How to take the screen off the page, if there's one.
+    pub fn remover(&self) -> Option<Box<dyn Fn()>> {
+        self.screen.as_ref().map(|s| s.remover())
+    }
+
+    /// Puts `text` up in place of whatever was there.
+    pub fn print(
+        &mut self,
+        ns: &NS,
+        text: &str,
+    ) {
+        if let Some(screen) = self.screen.as_mut() {
+            screen.draw(text);
+            return;
+        }
+
+        // the terminal keeps what was printed to it, as it can't be cleared
+        if let Mode::Terminal = self.mode {
+            ns.tprintRaw(&element(text));
+            return;
+        }
+
+        if self.printed == CLEAR_EVERY {
+            ns.clearLog();
+            self.printed = 0;
+        }
+        self.printed += 1;
+
+        match self.mode {
+            Mode::Ansi | Mode::Dom => ns.print(text),
+            Mode::React | Mode::Terminal => ns.printRaw(&element(text)),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Picking movies up where they were left off, for when the player gets
+//! killed partway through a long one.
+//!
+//! Every movie has a state file next to it with the index of the frame that
+//! the playback got to, which gets written every few seconds. Players started
+//! with `--instance` keep state files of their own. The index gets kept as
+//! a blob where the build can compress one, and as text where it can't.
+
+use crate::{
+    storage,
+    NS,
+};
+
+/// How often the state file gets written, in milliseconds.
+pub const SAVE_INTERVAL_MS: f64 = 5000.;
+
+/// The state file of the movie. Scripts can only write text files, so it
+/// ends up as `movie.resume.txt` for `movie.txt`, or `movie.name.resume.txt`
+/// for the instance `name`.
+fn state_file(
+    filename: &str,
+    instance: Option<&str>,
+) -> String {
+    let stem = filename.strip_suffix(".txt").unwrap_or(filename);
+
+    match instance {
+        Some(instance) => format!("{}.{}.resume.txt", stem, instance),
+        None => format!("{}.resume.txt", stem),
+    }
+}
+
+/// What's kept in the state file, which can be text from a build that
+/// doesn't compress it.
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+fn read_state(
+    ns: &NS,
+    state_file: &str,
+) -> Option<String> {
+    match storage::read_blob(ns, state_file) {
+        Ok(bytes) => String::from_utf8(bytes?).ok(),
+        Err(_) => storage::read_text(ns, state_file),
+    }
+}
+
+#[cfg(not(all(feature = "base64", any(feature = "lz4", feature = "deflate"))))]
+fn read_state(
+    ns: &NS,
+    state_file: &str,
+) -> Option<String> {
+    storage::read_text(ns, state_file)
+}
+
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+fn write_state(
+    ns: &NS,
+    state_file: &str,
+    state: &str,
+) {
+    if let Err(e) = storage::write_blob(ns, state_file, state.as_bytes()) {
+        log::warn!("cannot save to {}: {}", state_file, e);
+    }
+}
+
+#[cfg(not(all(feature = "base64", any(feature = "lz4", feature = "deflate"))))]
+fn write_state(
+    ns: &NS,
+    state_file: &str,
+    state: &str,
+) {
+    storage::write_text(ns, state_file, state);
+}
+
+/// The frame that the playback of the movie got to, if it got cut short.
+pub fn load(
+    ns: &NS,
+    filename: &str,
+    instance: Option<&str>,
+) -> Option<usize> {
+    read_state(ns, &state_file(filename, instance))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+pub fn save(
+    ns: &NS,
+    filename: &str,
+    instance: Option<&str>,
+    frame: usize,
+) {
+    let state_file = state_file(filename, instance);
+    write_state(ns, &state_file, &frame.to_string());
+}
+
+/// Empties the state file once the movie has played through, so that it
+/// starts from the beginning the next time.
+pub fn clear(
+    ns: &NS,
+    filename: &str,
+    instance: Option<&str>,
+) {
+    storage::clear(ns, &state_file(filename, instance));
+}
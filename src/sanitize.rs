@@ -0,0 +1,105 @@
+//! Tidying up the frames before they go up, so that one that's off can't
+//! spoil what gets printed after it: the escape sequences other than the
+//! colours go, every line gets padded or cut to the width of the movie, and
+//! the frame ends with its colours reset.
+//!
+//! The encoder never ends a frame with a reset, so a frame that's clean but
+//! for that gets borrowed as it is, and the reset gets written up after it
+//! with [`end_with_reset`], rather than every frame getting copied for it.
+
+use std::borrow::Cow;
+
+use crate::ansi::{
+    self,
+    Piece,
+};
+
+const RESET: &str = "\u{1b}[0m";
+
+/// `frame` tidied up for lines `width` characters wide, as it is if there's
+/// nothing to tidy but the reset at the end of it.
+pub fn sanitize(
+    frame: &str,
+    width: u32,
+) -> Cow<'_, str> {
+    let width = width as usize;
+
+    match is_clean(frame, width) {
+        true => Cow::Borrowed(frame),
+        false => Cow::Owned(clean(frame, width)),
+    }
+}
+
+fn is_clean(
+    frame: &str,
+    width: usize,
+) -> bool {
+    frame.lines().all(|line| {
+        let mut columns = 0;
+
+        for piece in ansi::pieces(line) {
+            match piece {
+                Piece::Text(text) if text.chars().any(char::is_control) => {
+                    return false;
+                },
+                Piece::Text(text) => columns += text.chars().count(),
+                Piece::Color(_) => {},
+                Piece::Escape(_) => return false,
+            }
+        }
+
+        columns == width
+    })
+}
+
+/// Resets the colours at the end of `shown`, before the newlines that it
+/// ends with, unless they already are.
+pub fn end_with_reset(shown: &mut String) {
+    let end = shown.trim_end_matches('\n').len();
+
+    if !shown[.. end].ends_with(RESET) {
+        shown.insert_str(end, RESET);
+    }
+}
+
+fn clean(
+    frame: &str,
+    width: usize,
+) -> String {
+    let mut cleaned = String::with_capacity(frame.len() + RESET.len());
+
+    for line in frame.lines() {
+        let mut columns = 0;
+
+        for piece in ansi::pieces(line) {
+            match piece {
+                Piece::Text(text) => {
+                    // control characters would throw the columns off
+                    for c in text.chars().take(width - columns) {
+                        match c.is_control() {
+                            true => cleaned.push(' '),
+                            false => cleaned.push(c),
+                        }
+                        columns += 1;
+                    }
+                },
+                Piece::Color(params) => {
+                    cleaned += "\u{1b}[";
+                    cleaned += params;
+                    cleaned += "m";
+                },
+                Piece::Escape(_) => {},
+            }
+        }
+
+        cleaned.extend(std::iter::repeat_n(' ', width - columns));
+        cleaned += "\n";
+    }
+
+    // the reset goes before the newline, for it to stay the last line
+    cleaned.pop();
+    cleaned += RESET;
+    cleaned += "\n";
+
+    cleaned
+}
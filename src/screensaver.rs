@@ -0,0 +1,197 @@
+//! Something to put up once the movies are over, for `--screensaver`, rather
+//! than leaving the last frame frozen: a starfield or falling code, worked
+//! out as it goes, for as long as the script runs.
+
+use crate::{
+    event_loop::{
+        self,
+        Next,
+    },
+    ns::ui,
+    tail::CharSize,
+    Killed,
+    NS,
+};
+
+/// The size of the screensaver, in characters.
+const COLUMNS: usize = 60;
+const ROWS: usize = 20;
+
+/// How long a frame stays up, in milliseconds.
+const FRAME_MS: f64 = 1000. / 15.;
+
+const RESET: &str = "\u{1b}[0m";
+
+#[derive(Clone, Copy)]
+pub enum Kind {
+    Starfield,
+    /// Code falling down the screen in streaks of green.
+    Matrix,
+}
+
+impl Kind {
+    pub fn parse(kind: &str) -> Option<Kind> {
+        match kind {
+            "starfield" => Some(Kind::Starfield),
+            "matrix" => Some(Kind::Matrix),
+            _ => None,
+        }
+    }
+}
+
+fn random() -> f64 {
+    js_sys::Math::random()
+}
+
+/// A star, with its position across the screen from -1 to 1, and how far
+/// off it is, from 0 to 1.
+struct Star {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Star {
+    fn new(z: f64) -> Star {
+        Star {
+            x: random() * 2. - 1.,
+            y: random() * 2. - 1.,
+            z,
+        }
+    }
+}
+
+/// How many stars there are at a time.
+const STARS: usize = 80;
+
+/// How much nearer the stars get with every frame.
+const STAR_SPEED: f64 = 0.02;
+
+fn starfield(stars: &mut [Star]) -> String {
+    let mut screen = vec![vec![' '; COLUMNS]; ROWS];
+
+    for star in stars.iter_mut() {
+        star.z -= STAR_SPEED;
+
+        // the stars spread out from the middle as they come nearer
+        let column = (star.x / star.z + 1.) / 2. * COLUMNS as f64;
+        let row = (star.y / star.z + 1.) / 2. * ROWS as f64;
+        let on_screen = (0. .. COLUMNS as f64).contains(&column)
+            && (0. .. ROWS as f64).contains(&row);
+
+        if star.z <= STAR_SPEED || !on_screen {
+            *star = Star::new(1.);
+            continue;
+        }
+
+        screen[row as usize][column as usize] = match star.z {
+            z if z < 0.3 => '@',
+            z if z < 0.6 => '*',
+            _ => '.',
+        };
+    }
+
+    let mut frame = String::from("\u{1b}[97;40m");
+    for row in screen {
+        frame.extend(row);
+        frame += "\n";
+    }
+    frame.pop();
+    frame += RESET;
+
+    frame
+}
+
+/// A streak of falling code, by where its head is and how fast it falls, in
+/// rows a frame.
+struct Streak {
+    head: f64,
+    speed: f64,
+    length: usize,
+}
+
+impl Streak {
+    fn new() -> Streak {
+        Streak {
+            // starting above the screen, for the streaks to come in apart
+            head: -(random() * ROWS as f64),
+            speed: 0.3 + random() * 0.7,
+            length: 4 + (random() * 12.) as usize,
+        }
+    }
+}
+
+/// The characters that the code is made of.
+const CODE: &[char] = &[
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'Z', ':', '.', '=', '*',
+    '+', '-', '<', '>', '|', '\u{ff66}', '\u{ff71}', '\u{ff73}', '\u{ff74}',
+    '\u{ff75}', '\u{ff76}', '\u{ff77}', '\u{ff79}', '\u{ff7a}', '\u{ff7b}',
+];
+
+fn matrix(streaks: &mut [Streak]) -> String {
+    let mut frame = String::new();
+
+    for streak in streaks.iter_mut() {
+        streak.head += streak.speed;
+
+        if ROWS + streak.length < streak.head as usize {
+            *streak = Streak::new();
+        }
+    }
+
+    for row in 0 .. ROWS {
+        for streak in streaks.iter() {
+            let behind = streak.head - row as f64;
+
+            // the head is the brightest, and the tail fades out
+            let color = match behind {
+                b if !(0. ..= streak.length as f64).contains(&b) => None,
+                b if b < 1. => Some(231),
+                b if b < streak.length as f64 / 2. => Some(46),
+                _ => Some(28),
+            };
+
+            match color {
+                Some(color) => {
+                    let index = (random() * CODE.len() as f64) as usize;
+                    let c = CODE[index.min(CODE.len() - 1)];
+                    frame += &format!("\u{1b}[38;5;{};40m{}", color, c);
+                },
+                None => frame += "\u{1b}[40m ",
+            }
+        }
+
+        frame += "\n";
+    }
+    frame.pop();
+    frame += RESET;
+
+    frame
+}
+
+/// Puts the screensaver up in the tail window, until the script gets
+/// killed.
+pub async fn run(
+    ns: &NS,
+    kind: Kind,
+    char_size: CharSize,
+) -> Result<(), Killed> {
+    let (width, height) = char_size.tail_size(COLUMNS as u32, ROWS as u32);
+    ui::resize_tail(ns, width, height);
+
+    let mut stars =
+        (0 .. STARS).map(|_| Star::new(random())).collect::<Vec<_>>();
+    let mut streaks = (0 .. COLUMNS).map(|_| Streak::new()).collect::<Vec<_>>();
+
+    event_loop::run_loop(ns, None, async |_| {
+        let frame = match kind {
+            Kind::Starfield => starfield(&mut stars),
+            Kind::Matrix => matrix(&mut streaks),
+        };
+
+        ns.clearLog();
+        ns.print(&frame);
+        Ok(Next::Wait(FRAME_MS))
+    })
+    .await
+}
@@ -0,0 +1,103 @@
+//! Which of the scripts the module is running for at the moment, for what
+//! doesn't get handed `ns`, like the logger and the panic hook, to go by.
+//!
+//! The loader keeps the one instance of the module for every script that
+//! runs it, so the jukebox, its players, and the players of `--instance`
+//! all share its statics. Each script's future has itself taken for the
+//! running one for as long as it's being polled, in [`run`], which is the
+//! only time that any of its code runs.
+
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+use crate::{
+    logger::Sink,
+    ns::SafeNs,
+    NS,
+};
+
+/// What there is to know about a script that runs the module.
+pub struct Script {
+    pub ns: SafeNs,
+    /// Where its messages other than the errors and the warnings go up.
+    pub sink: Cell<Sink>,
+    /// The frame that its playback is at, if it's at one.
+    pub frame: Cell<Option<usize>>,
+}
+
+thread_local! {
+    static RUNNING: RefCell<Option<Rc<Script>>> = const { RefCell::new(None) };
+}
+
+/// A future that has its script taken for the running one while it's polled.
+struct Running<F> {
+    script: Rc<Script>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Running<F> {
+    type Output = F::Output;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<F::Output> {
+        let this = self.get_mut();
+
+        // the one before is put back, for a script run from within another
+        let before = RUNNING.with(|r| r.replace(Some(this.script.clone())));
+        let poll = this.future.as_mut().poll(cx);
+        RUNNING.with(|r| *r.borrow_mut() = before);
+
+        poll
+    }
+}
+
+/// Runs `future` as the script of `ns`.
+pub async fn run<F: Future>(
+    ns: &NS,
+    future: F,
+) -> F::Output {
+    let script = Script {
+        ns: SafeNs::new(ns.clone()),
+        sink: Cell::new(Sink::default()),
+        frame: Cell::new(None),
+    };
+
+    Running {
+        script: Rc::new(script),
+        future: Box::pin(future),
+    }
+    .await
+}
+
+/// Looks at the script that's running, if any is.
+pub fn with<R>(f: impl FnOnce(&Script) -> R) -> Option<R> {
+    // there's none to be had if the panic came from taking it
+    RUNNING.with(|r| r.try_borrow().ok()?.as_deref().map(f))
+}
+
+/// Wraps `f` to run as the script that's running now, for what the game
+/// calls back once it's done polling, like the handler of `ns.atExit`.
+pub fn bind<R>(f: impl FnOnce() -> R) -> impl FnOnce() -> R {
+    let script = RUNNING.with(|r| r.borrow().clone());
+
+    move || {
+        let before = RUNNING.with(|r| r.replace(script));
+        let result = f();
+        RUNNING.with(|r| *r.borrow_mut() = before);
+
+        result
+    }
+}
@@ -0,0 +1,156 @@
+//! Tidying up once the script exits. Killing it leaves the playback in the
+//! middle of a frame, with the soundtrack still going and the tail window
+//! sized for the movie and titled for it, since nothing after the await that
+//! it got killed in ever runs. Nor does the report of how the playback went,
+//! which gets put up from here instead.
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    ns::{
+        args,
+        objects::get_field,
+        ui,
+    },
+    script,
+    NS,
+};
+
+/// The size that the tail window goes back to, which is the size that
+/// Bitburner opens it at.
+const TAIL_WIDTH: u32 = 500;
+const TAIL_HEIGHT: u32 = 500;
+
+/// What there is to undo at any point of the playback.
+struct State {
+    port: u32,
+    now_playing_port: u32,
+    /// The title that the tail window goes back to.
+    title: String,
+    /// Stops the soundtrack of the file being played, if it has one.
+    stop_audio: Option<Box<dyn Fn()>>,
+    /// Takes the screen of `--render dom` off the page, if there's one.
+    remove_screen: Option<Box<dyn Fn()>>,
+    /// Reports how the playback of the file being played has gone so far,
+    /// if there's one being played.
+    report: Option<Box<dyn Fn() -> String>>,
+    /// Whether the log and the tail window stay as they are, for the last
+    /// frame to stay up.
+    keep_log: bool,
+}
+
+/// The title of the tail window as the script started out with it, which the
+/// game puts together out of its filename and its arguments if it hasn't
+/// been set.
+fn title(ns: &NS) -> String {
+    let script = ns.getRunningScript().ok();
+    let field = |name| script.as_ref().and_then(|s| get_field(s, name).ok());
+    if let Some(title) = field("title") {
+        return title;
+    }
+
+    let filename = field("filename").unwrap_or_default();
+    let args = args::args(ns).map(|a| a.text());
+
+    std::iter::once(filename).chain(args).collect::<Vec<_>>().join(" ")
+}
+
+/// The handle to what gets undone once the script exits.
+pub struct Shutdown {
+    state: Rc<RefCell<State>>,
+}
+
+impl Shutdown {
+    /// Registers the tidying up with `ns.atExit`, for the player listening on
+    /// `port` and putting its state up on `now_playing_port`.
+    pub fn register(
+        ns: &NS,
+        port: u32,
+        now_playing_port: u32,
+    ) -> Shutdown {
+        let state = Rc::new(RefCell::new(State {
+            port,
+            now_playing_port,
+            title: title(ns),
+            stop_audio: None,
+            remove_screen: None,
+            report: None,
+            keep_log: false,
+        }));
+
+        let handler = {
+            let ns = ns.clone();
+            let state = state.clone();
+
+            // the game calls it outside of the polling of the script, which
+            // the logger goes by
+            Closure::once_into_js(script::bind(move || {
+                let state = state.borrow();
+
+                if let Some(report) = state.report.as_ref() {
+                    log::info!("{}", report());
+                }
+
+                if let Some(stop_audio) = state.stop_audio.as_ref() {
+                    stop_audio();
+                }
+
+                if let Some(remove_screen) = state.remove_screen.as_ref() {
+                    remove_screen();
+                }
+
+                // commands left on the port would go to the next player, and
+                // the state on the other one would never go stale
+                ns.clearPort(state.port);
+                ns.clearPort(state.now_playing_port);
+
+                if !state.keep_log {
+                    ns.clearLog();
+                    ui::resize_tail(&ns, TAIL_WIDTH, TAIL_HEIGHT);
+                    ui::set_tail_title(&ns, &state.title);
+                }
+            }))
+        };
+        ns.atExit(&handler);
+
+        Shutdown { state }
+    }
+
+    /// Sets how to stop the soundtrack of the file being played, or that
+    /// there isn't one.
+    pub fn set_audio(
+        &self,
+        stop_audio: Option<Box<dyn Fn()>>,
+    ) {
+        self.state.borrow_mut().stop_audio = stop_audio;
+    }
+
+    /// Sets how to take the screen of the file being played off the page, or
+    /// that there isn't one.
+    pub fn set_screen(
+        &self,
+        remove_screen: Option<Box<dyn Fn()>>,
+    ) {
+        self.state.borrow_mut().remove_screen = remove_screen;
+    }
+
+    /// Sets how to report how the playback of the file being played has
+    /// gone, or that there's none being played.
+    pub fn set_report(
+        &self,
+        report: Option<Box<dyn Fn() -> String>>,
+    ) {
+        self.state.borrow_mut().report = report;
+    }
+
+    /// Leaves the log and the tail window be, so that whatever was printed
+    /// last stays up.
+    pub fn keep_log(&self) {
+        self.state.borrow_mut().keep_log = true;
+    }
+}
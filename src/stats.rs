@@ -0,0 +1,92 @@
+//! What the playback of a movie went like, reported as it ends, to tell a
+//! file that stutters apart from a player that can't keep up.
+
+use crate::performance_now;
+
+pub const MEGABYTE: usize = 1024 * 1024;
+
+#[derive(Default)]
+pub struct Stats {
+    pub shown: usize,
+    pub dropped: usize,
+    /// The longest that a frame went up after it was due, in milliseconds.
+    pub max_lateness_ms: f64,
+    /// How long decompressing and skipping frames took, in milliseconds.
+    pub decode_ms: f64,
+    /// When the first frame went up, if it has.
+    pub started: Option<f64>,
+    /// How long the playback was paused for since, in milliseconds.
+    pub paused_ms: f64,
+    /// The most bytes that the movie and its frames took up at once.
+    pub peak_memory: usize,
+    /// The frames that were corrupt, and had the one before go up in their
+    /// place.
+    pub corrupt: Vec<usize>,
+}
+
+impl Stats {
+    /// Runs `decode`, counting the time that it takes.
+    pub fn time_decode<T>(
+        &mut self,
+        decode: impl FnOnce() -> T,
+    ) -> T {
+        let started = performance_now();
+        let decoded = decode();
+        self.decode_ms += performance_now() - started;
+
+        decoded
+    }
+
+    /// Counts the bytes taken up at the moment towards the peak.
+    pub fn record_memory(
+        &mut self,
+        bytes: usize,
+    ) {
+        self.peak_memory = self.peak_memory.max(bytes);
+    }
+
+    /// How long the playback has run for, pauses excluded, in milliseconds.
+    fn playing_ms(&self) -> f64 {
+        match self.started {
+            Some(started) => performance_now() - started - self.paused_ms,
+            None => 0.,
+        }
+    }
+
+    /// The report, as of now, which drops nothing that the playback got to
+    /// for being cut short.
+    pub fn report(
+        &self,
+        framerate: f64,
+    ) -> String {
+        let playing_ms = self.playing_ms();
+        let fps = match 0. < playing_ms {
+            true => self.shown as f64 * 1000. / playing_ms,
+            false => 0.,
+        };
+
+        let mut report = format!(
+            "{} frames shown, {} dropped, {:.1} of {:.1} fps, at most {:.0} ms \
+             late, {:.0} ms decoding, at most {:.1} MB in memory",
+            self.shown,
+            self.dropped,
+            fps,
+            framerate,
+            self.max_lateness_ms,
+            self.decode_ms,
+            self.peak_memory as f64 / MEGABYTE as f64,
+        );
+
+        if !self.corrupt.is_empty() {
+            let frames = self
+                .corrupt
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>();
+
+            report += &format!(", corrupt frames {}", frames.join(", "));
+        }
+
+        report
+    }
+}
@@ -0,0 +1,94 @@
+//! The status line under the frames: whether the movie is playing, how far
+//! into it the playback is, and a progress bar once its length is known. The
+//! title of the tail window says as much, for when the line is hidden.
+
+use crate::format_time;
+
+/// The narrowest that the progress bar gets before it's left out.
+const MIN_BAR_WIDTH: usize = 4;
+
+/// Renders the status line at `width` characters, the position and length
+/// being in seconds. A `stride` past 1 is the playback going up only every so
+/// many frames to keep up, which gets pointed out.
+pub fn status_line(
+    position: f64,
+    length: Option<f64>,
+    paused: bool,
+    stride: usize,
+    width: u32,
+) -> String {
+    let width = width as usize;
+
+    let icon = match paused {
+        true => "\u{275a}\u{275a}",
+        false => "\u{25b6} ",
+    };
+
+    let mut line = match length {
+        Some(length) => {
+            format!(
+                "{} {} / {}",
+                icon,
+                format_time(position),
+                format_time(length)
+            )
+        },
+        None => format!("{} {}", icon, format_time(position)),
+    };
+
+    if 1 < stride {
+        line += &format!(" (1/{} frames)", stride);
+    }
+
+    // the bar takes up whatever is left, past a space and its brackets
+    let left = width.saturating_sub(line.chars().count() + 3);
+    if let Some(length) = length.filter(|_| MIN_BAR_WIDTH <= left) {
+        let progress = match 0. < length {
+            true => (position / length).clamp(0., 1.),
+            false => 0.,
+        };
+        let filled = (progress * left as f64).round() as usize;
+
+        line += " [";
+        line.extend(std::iter::repeat_n('=', filled));
+        line.extend(std::iter::repeat_n('-', left - filled));
+        line += "]";
+    }
+
+    let padding = width.saturating_sub(line.chars().count());
+
+    let mut output = String::from("\u{1b}[0;97;40m");
+    output += &line;
+    output.extend(std::iter::repeat_n(' ', padding));
+    output += "\u{1b}[0m\n";
+    output
+}
+
+/// Titles the tail window after `title`, with the position and the length in
+/// seconds and whether the movie is playing, like `Title — 1:23 / 4:56 ▶`.
+pub fn title(
+    title: &str,
+    position: f64,
+    length: Option<f64>,
+    paused: bool,
+) -> String {
+    let icon = match paused {
+        true => "\u{275a}\u{275a}",
+        false => "\u{25b6}",
+    };
+
+    match length {
+        Some(length) => {
+            format!(
+                "{} \u{2014} {} / {} {}",
+                title,
+                format_time(position),
+                format_time(length),
+                icon,
+            )
+        },
+        None => {
+            format!("{} \u{2014} {} {}", title, format_time(position), icon)
+        },
+    }
+}
@@ -0,0 +1,135 @@
+//! The files that the player keeps things in between runs, like the resume
+//! and calibration files, read and written the one way.
+//!
+//! A file that's empty counts as not being there, since scripts empty files
+//! more often than they remove them. What isn't text gets kept as the base64
+//! of it compressed, the way that the encoder writes the movies.
+
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+use std::io::{
+    self,
+    Read,
+};
+
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+use base64::engine::Engine as _;
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+use bitburner_video::Decompressor;
+
+use crate::NS;
+
+/// The text of the file, unless it's empty or isn't there.
+pub fn read_text(
+    ns: &NS,
+    filename: &str,
+) -> Option<String> {
+    non_empty(ns.read(filename))
+}
+
+fn non_empty(text: String) -> Option<String> {
+    Some(text).filter(|text| !text.is_empty())
+}
+
+pub fn write_text(
+    ns: &NS,
+    filename: &str,
+    text: &str,
+) {
+    ns.write(filename, text, "w");
+}
+
+/// Empties the file.
+pub fn clear(
+    ns: &NS,
+    filename: &str,
+) {
+    ns.write(filename, "", "w");
+}
+
+/// The lines of the file, trimmed, skipping blank lines and `#` comments,
+/// as for a list of filenames.
+pub fn read_lines(
+    ns: &NS,
+    filename: &str,
+) -> Vec<String> {
+    lines(&ns.read(filename))
+}
+
+fn lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.to_owned())
+        .collect()
+}
+
+/// The bytes kept in the file by [`write_blob`], unless it's empty or isn't
+/// there.
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+pub fn read_blob(
+    ns: &NS,
+    filename: &str,
+) -> io::Result<Option<Vec<u8>>> {
+    read_text(ns, filename).map(|text| decode_blob(&text)).transpose()
+}
+
+/// Keeps the bytes in the file, compressed and then as base64.
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+pub fn write_blob(
+    ns: &NS,
+    filename: &str,
+    bytes: &[u8],
+) -> io::Result<()> {
+    write_text(ns, filename, &encode_blob(bytes)?);
+    Ok(())
+}
+
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+fn encode_blob(bytes: &[u8]) -> io::Result<String> {
+    let compressed = bitburner_video::compress(bytes)?;
+    Ok(base64::prelude::BASE64_STANDARD.encode(compressed))
+}
+
+#[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+fn decode_blob(text: &str) -> io::Result<Vec<u8>> {
+    let compressed = base64::prelude::BASE64_STANDARD
+        .decode(text.trim())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut bytes = vec![];
+    Decompressor::new(&compressed[..])?.read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_file_isnt_there() {
+        assert_eq!(non_empty(String::new()), None);
+        assert_eq!(non_empty("12 30".to_owned()).as_deref(), Some("12 30"));
+        assert_eq!(non_empty("\n".to_owned()).as_deref(), Some("\n"));
+    }
+
+    #[test]
+    fn lines_skip_blanks_and_comments() {
+        let playlist = "# the first\n one.txt \n\n\t\n#two.txt\nthree.txt";
+
+        assert_eq!(lines(playlist), ["one.txt", "three.txt"]);
+        assert!(lines("").is_empty());
+    }
+
+    #[cfg(all(feature = "base64", any(feature = "lz4", feature = "deflate")))]
+    #[test]
+    fn blobs_read_back_as_they_were_written() {
+        let bytes = b"1234\n\x00\xff".repeat(10);
+        let text = encode_blob(&bytes).unwrap();
+
+        assert!(text.is_ascii());
+        assert_eq!(decode_blob(&text).unwrap(), bytes);
+        assert_eq!(decode_blob(&format!("{}\n", text)).unwrap(), bytes);
+        assert!(decode_blob("1234").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Subtitles drawn over the bottom rows of the frames.
+
+pub struct Cue {
+    pub start_ms: f64,
+    pub end_ms: f64,
+    pub lines: Vec<String>,
+}
+
+/// A subtitle track in one language.
+pub struct Subtitles {
+    pub language: String,
+    /// Sorted by start time.
+    cues: Vec<Cue>,
+}
+
+/// Reads cues the way the chunks with timed text hold them: separated by
+/// blank lines, each a `<start> <end>` line followed by the lines of text.
+/// The cues come out sorted by start time.
+pub fn parse_cues(payload: &str) -> Option<Vec<Cue>> {
+    let mut cues = vec![];
+
+    for block in payload.split("\n\n") {
+        let mut lines = block.lines();
+        let Some((start, end)) = lines.next().and_then(|l| l.split_once(' '))
+        else {
+            continue;
+        };
+
+        cues.push(Cue {
+            start_ms: start.parse().ok()?,
+            end_ms: end.parse().ok()?,
+            lines: lines.map(|l| l.to_owned()).collect(),
+        });
+    }
+
+    cues.sort_by(|a, b| a.start_ms.total_cmp(&b.start_ms));
+    Some(cues)
+}
+
+/// Of the cues that have started by `time_ms`, the latest one that hasn't
+/// ended. The cues must be sorted by start time.
+pub fn active(
+    cues: &[Cue],
+    time_ms: f64,
+) -> Option<&Cue> {
+    let started = cues.partition_point(|c| c.start_ms <= time_ms);
+    cues[.. started].iter().rev().find(|c| time_ms < c.end_ms)
+}
+
+impl Subtitles {
+    /// Reads the cues out of the payload of a subtitle chunk.
+    pub fn from_chunk(payload: &[u8]) -> Option<Subtitles> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let (language, payload) = payload.split_once('\n')?;
+
+        Some(Subtitles {
+            language: language.to_owned(),
+            cues: parse_cues(payload)?,
+        })
+    }
+
+    /// The cue to show at `time_ms` into the movie, if any.
+    pub fn active(
+        &self,
+        time_ms: f64,
+    ) -> Option<&Cue> {
+        active(&self.cues, time_ms)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Position {
+    Top,
+    Bottom,
+}
+
+/// How the subtitles look.
+pub struct Style {
+    /// The SGR code of the text color.
+    pub color: u8,
+    pub position: Position,
+    /// The widest that a line can get before it wraps, in characters. The
+    /// width of the frame is the limit either way.
+    pub max_width: Option<usize>,
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            color: 97,
+            position: Position::Bottom,
+            max_width: None,
+        }
+    }
+}
+
+impl Position {
+    pub fn parse(position: &str) -> Option<Position> {
+        match position {
+            "top" => Some(Position::Top),
+            "bottom" => Some(Position::Bottom),
+            _ => None,
+        }
+    }
+
+    /// The other edge of the frame.
+    pub fn opposite(self) -> Position {
+        match self {
+            Position::Top => Position::Bottom,
+            Position::Bottom => Position::Top,
+        }
+    }
+}
+
+/// Turns the name of a color into the SGR code of its bright variant.
+pub fn parse_color(color: &str) -> Option<u8> {
+    match color {
+        "white" => Some(97),
+        "yellow" => Some(93),
+        "cyan" => Some(96),
+        "green" => Some(92),
+        "magenta" => Some(95),
+        "red" => Some(91),
+        "blue" => Some(94),
+        _ => None,
+    }
+}
+
+/// Word-wraps the lines to the width. Words that are wider than that on their
+/// own get split.
+pub fn wrap(
+    lines: &[String],
+    width: usize,
+) -> Vec<String> {
+    let width = width.max(1);
+    let mut wrapped = vec![];
+
+    for line in lines {
+        let mut current = String::new();
+        let mut current_width = 0;
+
+        for word in line.split_whitespace() {
+            let mut word = word.chars().collect::<Vec<_>>();
+
+            while !word.is_empty() {
+                let separator = usize::from(current_width != 0);
+
+                if current_width + separator + word.len() <= width {
+                    if separator != 0 {
+                        current.push(' ');
+                    }
+
+                    current.extend(word.iter());
+                    current_width += separator + word.len();
+                    word.clear();
+                }
+                else if current_width != 0 {
+                    wrapped.push(std::mem::take(&mut current));
+                    current_width = 0;
+                }
+                else {
+                    let rest = word.split_off(width);
+                    wrapped.push(word.iter().collect());
+                    word = rest;
+                }
+            }
+        }
+
+        if current_width != 0 {
+            wrapped.push(current);
+        }
+    }
+
+    wrapped
+}
+
+/// Replaces the top or bottom rows of the frame with the wrapped lines of the
+/// cue, centered on a black background.
+pub fn overlay(
+    frame: &str,
+    cue: &Cue,
+    width: u32,
+    style: &Style,
+) -> String {
+    let width = width as usize;
+    let max_width = style.max_width.map_or(width, |w| w.min(width));
+    let lines = wrap(&cue.lines, max_width);
+
+    let rows = frame.lines().collect::<Vec<_>>();
+    let first_text_row = match style.position {
+        Position::Top => 0,
+        Position::Bottom => rows.len().saturating_sub(lines.len()),
+    };
+
+    let mut output = String::with_capacity(frame.len());
+
+    for (index, row) in rows.iter().enumerate() {
+        let text = index
+            .checked_sub(first_text_row)
+            .and_then(|line| lines.get(line));
+
+        let Some(text) = text
+        else {
+            output += row;
+            output += "\n";
+            continue;
+        };
+
+        // a frame narrower than a character still gets the one, unpadded
+        let padding = width.saturating_sub(text.chars().count());
+        let left = padding / 2;
+
+        output += &format!("\u{1b}[0;{};40m", style.color);
+        output.extend(std::iter::repeat_n(' ', left));
+        output += text;
+        output.extend(std::iter::repeat_n(' ', padding - left));
+        output += "\u{1b}[0m\n";
+    }
+
+    output
+}
@@ -0,0 +1,113 @@
+//! Sizing the tail window to fit the frames, which takes knowing how large a
+//! character of it is in pixels. That depends on the font of the game and
+//! the zoom of the page, so it's taken, first of what's there, from
+//! `--char-size`, from a calibration file, or from the styles of the game.
+
+use crate::{
+    ns::{
+        objects::{
+            get_field,
+            TailProperties,
+        },
+        ui,
+    },
+    storage,
+    NS,
+};
+
+/// The size of the tail window, in pixels, unless it's closed. The running
+/// script has it as well, for the games from before `getTailProperties`.
+pub fn size(ns: &NS) -> Option<(f64, f64)> {
+    let properties = match ui::tail_properties(ns) {
+        Some(properties) => properties,
+        None => {
+            let script = ns.getRunningScript().ok()?;
+            get_field::<Option<TailProperties>>(&script, "tailProperties")
+                .ok()??
+        },
+    };
+
+    Some((properties.width, properties.height))
+}
+
+/// The file with the size of a character as `<width> <height>`, as written by
+/// hand or by `--calibrate`.
+pub const CALIBRATION_FILE: &str = "bitoxide.calibration.txt";
+
+/// How much wider a character of a monospace font is than its size.
+const CHAR_WIDTH_PER_FONT_SIZE: f64 = 0.6;
+
+/// The size of a character, in pixels.
+#[derive(Clone, Copy)]
+pub struct CharSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for CharSize {
+    /// What a character takes up with the font that the game starts out
+    /// with, more or less.
+    fn default() -> CharSize {
+        CharSize {
+            width: 10.,
+            height: 30.,
+        }
+    }
+}
+
+impl CharSize {
+    /// Parses the size as `10x30`, or as `10 30` like the calibration file.
+    pub fn parse(size: &str) -> Option<CharSize> {
+        let (width, height) = size.trim().split_once(['x', ' '])?;
+        let width = width.trim().parse::<f64>().ok()?;
+        let height = height.trim().parse::<f64>().ok()?;
+
+        let valid = |d: f64| d.is_finite() && 0. < d;
+        (valid(width) && valid(height)).then_some(CharSize { width, height })
+    }
+
+    /// Reads the size out of the calibration file, or works it out from the
+    /// font of the game if there's no such file.
+    pub fn detect(ns: &NS) -> CharSize {
+        storage::read_text(ns, CALIBRATION_FILE)
+            .and_then(|size| CharSize::parse(&size))
+            .or_else(|| CharSize::from_styles(ns))
+            .unwrap_or_default()
+    }
+
+    fn from_styles(ns: &NS) -> Option<CharSize> {
+        let styles = ui::styles(ns)?;
+        let line_height = styles.line_height.unwrap_or(1.5);
+
+        Some(CharSize {
+            width: styles.font_size * CHAR_WIDTH_PER_FONT_SIZE,
+            height: styles.font_size * line_height,
+        })
+    }
+
+    /// The size of a tail window that fits `columns` by `rows` characters, in
+    /// pixels.
+    pub fn tail_size(
+        &self,
+        columns: u32,
+        rows: u32,
+    ) -> (u32, u32) {
+        let width = (columns as f64 * self.width).ceil() as u32;
+        let height = (rows as f64 * self.height).ceil() as u32;
+
+        (width, height)
+    }
+
+    /// How many columns and rows of characters fit in a tail window of
+    /// `width` by `height` pixels.
+    pub fn fits(
+        &self,
+        width: f64,
+        height: f64,
+    ) -> (u32, u32) {
+        let columns = (width / self.width).floor() as u32;
+        let rows = (height / self.height).floor() as u32;
+
+        (columns, rows)
+    }
+}
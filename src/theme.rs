@@ -0,0 +1,80 @@
+//! Tinting the movie with the primary colour of the theme of the game, for
+//! `--theme-tint`, so that a movie in greys blends in with however the game
+//! has been made to look. The colours of their own are left be.
+
+use crate::{
+    ansi::{
+        self,
+        Color,
+    },
+    ns::ui,
+    NS,
+};
+
+/// How far apart the red, green, and blue of a colour can be for it to still
+/// count as a grey.
+const GREY_TOLERANCE: u8 = 24;
+
+pub struct Tint {
+    primary: (u8, u8, u8),
+}
+
+impl Tint {
+    /// Takes the primary colour out of the theme, unless the game doesn't
+    /// give one that's `#rgb` or `#rrggbb`.
+    pub fn from_theme(ns: &NS) -> Option<Tint> {
+        let theme = ui::theme(ns)?;
+
+        Some(Tint {
+            primary: parse_hex(&theme.primary?)?,
+        })
+    }
+
+    /// Tints the greys of `frame` with the primary colour, as bright as the
+    /// greys were.
+    pub fn apply(
+        &self,
+        frame: &str,
+    ) -> String {
+        let (red, green, blue) = self.primary;
+
+        ansi::recolor(frame, |_, color| {
+            let Some((r, g, b)) = color.rgb()
+            else {
+                return color;
+            };
+
+            if GREY_TOLERANCE < r.max(g).max(b) - r.min(g).min(b) {
+                return color;
+            }
+
+            let brightness =
+                (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64) / 255.;
+            let tint = |c: u8| (c as f64 * brightness).round() as u8;
+
+            Color::Rgb(tint(red), tint(green), tint(blue))
+        })
+    }
+}
+
+/// Parses a colour as CSS has it, like `#0c0` or `#00cc00`.
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#').filter(|h| h.is_ascii())?;
+    let channel = |c: &str| u8::from_str_radix(c, 16).ok();
+
+    match hex.len() {
+        3 => {
+            // each digit stands for two of itself
+            let digit = |i: usize| Some(channel(&hex[i .. i + 1])? * 17);
+            Some((digit(0)?, digit(1)?, digit(2)?))
+        },
+        6 => {
+            Some((
+                channel(&hex[0 .. 2])?,
+                channel(&hex[2 .. 4])?,
+                channel(&hex[4 .. 6])?,
+            ))
+        },
+        _ => None,
+    }
+}
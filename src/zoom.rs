@@ -0,0 +1,64 @@
+//! Blowing the frames up, for `--zoom`, by putting every character up as
+//! many times over, and every row as well with `--zoom-rows`, for a small
+//! movie to fill a large tail window.
+
+use std::borrow::Cow;
+
+use crate::ansi::{
+    self,
+    Piece,
+    Style,
+};
+
+/// `frame` with every character `columns` times over, and every row `rows`
+/// times over, as it is if neither is more than once.
+pub fn zoom(
+    frame: &str,
+    columns: u32,
+    rows: u32,
+) -> Cow<'_, str> {
+    if columns <= 1 && rows <= 1 {
+        return Cow::Borrowed(frame);
+    }
+
+    let columns = columns as usize;
+    let size = frame.len() * columns * rows as usize;
+    let mut zoomed = String::with_capacity(size);
+    let mut style = Style::default();
+
+    for line in frame.lines() {
+        // the copies of the row start out the same as the row did
+        let start = style;
+        let mut row = String::with_capacity(line.len() * columns);
+
+        for piece in ansi::pieces(line) {
+            match piece {
+                Piece::Text(text) => {
+                    for c in text.chars() {
+                        row.extend(std::iter::repeat_n(c, columns));
+                    }
+                },
+                Piece::Color(params) => {
+                    style.apply(params);
+                    row += "\u{1b}[";
+                    row += params;
+                    row += "m";
+                },
+                Piece::Escape(escape) => row += escape,
+            }
+        }
+
+        for copy in 0 .. rows.max(1) {
+            if 0 < copy {
+                zoomed += "\u{1b}[";
+                zoomed += &start.code();
+                zoomed += "m";
+            }
+
+            zoomed += &row;
+            zoomed += "\n";
+        }
+    }
+
+    Cow::Owned(zoomed)
+}
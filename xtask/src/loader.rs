@@ -0,0 +1,77 @@
+//! The script that the game runs, which is the glue of wasm-bindgen up to
+//! its own initialiser, and then `addendum.js`, which initialises the module
+//! out of its base64 and hands `ns` over to it. The game keeps nothing but
+//! text, so the wasm goes next to the script as base64, in a file of its own,
+//! or into the script itself, for one file to copy over rather than two.
+
+use std::io;
+
+use base64::engine::Engine as _;
+use bitburner_remote::File;
+
+const ADDENDUM: &str = include_str!("../../addendum.js");
+
+/// How long the lines of the embedded wasm get, like those of the code.
+const LINE_LEN: usize = 80;
+
+/// Where the initialisers of the glue start, which go by `fetch` and the URL
+/// of the module, neither of which the game has for a script.
+const GLUE_END: &str = "function initSync(module) {";
+
+/// The wasm as a string of JavaScript, split over lines that each end in a
+/// backslash, which carries the string on over the newline.
+fn embedded(wasm_b64: &str) -> String {
+    let mut embedded = String::from("const wasm_b64 = \"\\\n");
+
+    // base64 is all ASCII, so any byte is the end of a character
+    for line in wasm_b64.as_bytes().chunks(LINE_LEN - 1) {
+        embedded += std::str::from_utf8(line).unwrap_or_default();
+        embedded += "\\\n";
+    }
+
+    embedded += "\";\n";
+    embedded
+}
+
+/// The script named `name`, and the wasm that goes with it unless it's
+/// `embed`ded, out of the output of wasm-bindgen.
+pub fn files(
+    name: &str,
+    glue: &str,
+    wasm: &[u8],
+    embed: bool,
+) -> io::Result<Vec<File>> {
+    let end = glue.find(GLUE_END).ok_or_else(|| {
+        io::Error::other("the glue has no initSync, from another wasm-bindgen")
+    })?;
+
+    let wasm_b64 = base64::prelude::BASE64_STANDARD.encode(wasm);
+    let wasm_filename = format!("{}.wasm.txt", name);
+
+    let mut script = match embed {
+        true => {
+            embedded(&wasm_b64) + "const read_wasm_b64 = () => wasm_b64;\n\n"
+        },
+        false => {
+            format!(
+                "const read_wasm_b64 = (ns) => ns.read({:?});\n\n",
+                wasm_filename,
+            )
+        },
+    };
+    script += &glue[.. end];
+    script += ADDENDUM;
+
+    let mut files = vec![File {
+        filename: format!("{}.js", name),
+        content: script,
+    }];
+    if !embed {
+        files.push(File {
+            filename: wasm_filename,
+            content: wasm_b64,
+        });
+    }
+
+    Ok(files)
+}
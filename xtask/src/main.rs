@@ -0,0 +1,146 @@
+//! The tasks of building the player, as `cargo xtask <task>`, which so far
+//! is `deploy`: building the wasm, running wasm-bindgen over it, putting the
+//! script for the game together, and pushing it into the game through its
+//! Remote API, if asked to.
+
+mod loader;
+
+use std::{
+    env,
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+use bitburner_remote::File;
+use clap::Parser;
+
+/// The name of the player, as a crate and as a script of the game.
+const CRATE_NAME: &str = "bitoxide";
+
+const TARGET: &str = "wasm32-unknown-unknown";
+
+#[derive(Parser)]
+enum Task {
+    /// Build the player into target/deploy, for the game to run
+    Deploy(Deploy),
+}
+
+#[derive(clap::Args)]
+struct Deploy {
+    /// Build with only these features of the player, comma separated, rather
+    /// than the default ones
+    #[clap(long)]
+    features: Option<String>,
+    /// Keep the debug info in the wasm, for the profiler of the browser
+    #[clap(long)]
+    keep_debug: bool,
+    /// Put the wasm into the script as base64, rather than into a file of
+    /// its own next to it, for the player to be the one file
+    #[clap(long)]
+    embed: bool,
+    /// Push the player and remote.js into the game through its Remote API
+    #[clap(long)]
+    upload: bool,
+    /// The server of the game that --upload pushes onto
+    #[clap(long, default_value = "home")]
+    server: String,
+    /// The port that the game connects to for --upload, as set under
+    /// Options > Remote API
+    #[clap(long, default_value_t = bitburner_remote::DEFAULT_PORT)]
+    port: u16,
+}
+
+/// The directory of the player, which the xtask is in.
+fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("the xtask isn't in the player")
+        .to_owned()
+}
+
+/// Runs `command`, as failing if it doesn't exit with success.
+fn run(command: &mut Command) -> io::Result<()> {
+    let status = command.status()?;
+
+    match status.success() {
+        true => Ok(()),
+        false => {
+            Err(io::Error::other(format!(
+                "{:?} failed with {}",
+                command.get_program(),
+                status,
+            )))
+        },
+    }
+}
+
+fn deploy(deploy: &Deploy) -> io::Result<()> {
+    let root = root();
+    let target_dir = root.join("target");
+
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+    let mut build = Command::new(cargo);
+    build
+        .current_dir(&root)
+        .args(["build", "--release", "--target", TARGET]);
+    if let Some(features) = &deploy.features {
+        build.args(["--no-default-features", "--features", features]);
+    }
+    run(&mut build)?;
+
+    let wasm = target_dir
+        .join(TARGET)
+        .join("release")
+        .join(format!("{}.wasm", CRATE_NAME));
+    let bindgen_dir = target_dir.join("wasm_output");
+
+    let mut bindgen = Command::new("wasm-bindgen");
+    bindgen
+        .args(["--target", "web", "--no-typescript", "--out-dir"])
+        .arg(&bindgen_dir)
+        .arg(&wasm);
+    if deploy.keep_debug {
+        bindgen.arg("--keep-debug");
+    }
+    run(&mut bindgen)?;
+
+    let glue = bindgen_dir.join(format!("{}.js", CRATE_NAME));
+    let glue = fs::read_to_string(glue)?;
+    let wasm = bindgen_dir.join(format!("{}_bg.wasm", CRATE_NAME));
+    let wasm = fs::read(wasm)?;
+
+    let mut files = loader::files(CRATE_NAME, &glue, &wasm, deploy.embed)?;
+    // the remote for the player goes along with it
+    files.push(File {
+        filename: "remote.js".to_owned(),
+        content: fs::read_to_string(root.join("remote.js"))?,
+    });
+
+    // what's left of a deploy the other way would only confuse
+    let deploy_dir = target_dir.join("deploy");
+    if deploy_dir.exists() {
+        fs::remove_dir_all(&deploy_dir)?;
+    }
+    fs::create_dir_all(&deploy_dir)?;
+    for file in &files {
+        fs::write(deploy_dir.join(&file.filename), &file.content)?;
+    }
+    eprintln!("the player is in {}", deploy_dir.display());
+
+    if deploy.upload {
+        bitburner_remote::push_files(deploy.port, &deploy.server, &files)?;
+    }
+
+    Ok(())
+}
+
+fn main() {
+    match Task::parse() {
+        Task::Deploy(options) => deploy(&options).unwrap(),
+    }
+}